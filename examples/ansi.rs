@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    strip_ansi, AnsiParser, BevyLocalCommandsPlugin, LocalCommand, ProcessCompleted, ProcessOutput,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS, the output is colored like compiler output
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("printf").arg(
+        "\\033[1;31merror\\033[0m: \\033[1mmismatched types\\033[0m\\n\\033[32mexpected\\033[39m `u32`, \\033[38;2;255;128;0mfound\\033[0m `i64`\\n",
+    );
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell").args([
+        "Write-Output \"$([char]27)[1;31merror$([char]27)[0m: $([char]27)[1mmismatched types$([char]27)[0m\"",
+    ]);
+
+    let id = commands.spawn(cmd).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut parser: Local<AnsiParser>,
+) {
+    for process_output in process_output_event.read() {
+        for line in process_output.lines() {
+            println!("Stripped: {}", strip_ansi(line));
+            for span in parser.parse(line) {
+                println!("  {:?} {:?}", span.text, span.style);
+            }
+        }
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, OutputMode, ProcessCompleted, ProcessOutput,
+    ProcessOutputBytes,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS, the output is not valid UTF-8
+    #[cfg(not(windows))]
+    let cmd = || LocalCommand::new("printf").arg("\\211PNG\\r\\n\\032\\n\\377 Not UTF-8\\n");
+    #[cfg(windows)]
+    let cmd = || {
+        LocalCommand::new("powershell").args([
+            "[Console]::OpenStandardOutput().Write([byte[]](0x89,0x50,0x4E,0x47,0xFF,0x0A), 0, 6)",
+        ])
+    };
+
+    let bytes = commands.spawn(cmd().output_mode(OutputMode::Bytes)).id();
+    let lossy = commands
+        .spawn(cmd().output_mode(OutputMode::LossyText))
+        .id();
+    println!("Spawned the commands as entities {bytes:?} (bytes) and {lossy:?} (lossy text)");
+}
+
+fn update(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_output_bytes_event: EventReader<ProcessOutputBytes>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut completed: Local<usize>,
+) {
+    for process_output in process_output_bytes_event.read() {
+        println!(
+            "Output Bytes ({:?}): {:?}",
+            process_output.entity,
+            process_output.bytes()
+        );
+    }
+    for process_output in process_output_event.read() {
+        for line in process_output.lines() {
+            println!("Output Line ({:?}): {line}", process_output.entity);
+        }
+    }
+    *completed += process_completed_event.read().count();
+    if *completed == 2 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, CaptureOutput, LocalCommand, ProcessFinishedWithOutput,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the commands based on the OS
+    #[cfg(not(windows))]
+    let (short, long) = (
+        LocalCommand::new("sh").args(["-c", "echo 'Some output'; echo 'Some error' >&2"]),
+        LocalCommand::new("sh").args(["-c", "for i in $(seq 1 1000); do echo \"Line $i\"; done"]),
+    );
+    #[cfg(windows)]
+    let (short, long) = (
+        LocalCommand::new("powershell")
+            .args(["echo 'Some output'; [Console]::Error.WriteLine('Some error')"]),
+        LocalCommand::new("powershell").args(["1..1000 | ForEach-Object { \"Line $_\" }"]),
+    );
+
+    let short = commands.spawn((short, CaptureOutput::new())).id();
+    // Only keep the first 32 bytes of each stream
+    let long = commands
+        .spawn((long, CaptureOutput::new().max_bytes(32)))
+        .id();
+    println!("Spawned the commands as entities {short:?} and {long:?}");
+}
+
+fn update(mut finished_events: EventReader<ProcessFinishedWithOutput>, mut finished: Local<usize>) {
+    for output in finished_events.read() {
+        println!(
+            "Command {:?} finished ({}), truncated: {}\n  stdout: {:?}\n  stderr: {:?}",
+            output.entity,
+            output.exit_status,
+            output.truncated,
+            output.stdout_text(),
+            output.stderr_text()
+        );
+        *finished += 1;
+    }
+    if *finished == 2 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
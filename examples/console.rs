@@ -0,0 +1,104 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, ProcessCompleted, ProcessConsole, ProcessOutput,
+};
+
+fn main() {
+    // Runs without a window: use DefaultPlugins to see and type into the console
+    App::new()
+        .add_plugins((MinimalPlugins, InputPlugin, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, (type_name, update))
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args([
+        "-c",
+        "echo 'Enter Name:' && read NAME && printf '\\033[32mHello\\033[0m %s\\n' $NAME && echo Bye >&2",
+    ]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell").args([
+        "$name = Read-Host 'Enter Name'; echo \"Hello $name\"; [Console]::Error.WriteLine('Bye')",
+    ]);
+
+    let shell = commands.spawn(cmd).id();
+    let console = commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            ProcessConsole::new(shell).scrollback(100).focused(true),
+        ))
+        .id();
+    println!("Spawned the command as entity {shell:?}, shown in console {console:?}");
+}
+
+/// Simulate typing a name and pressing Enter once the command asks for it.
+fn type_name(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut keyboard_input_events: EventWriter<KeyboardInput>,
+) {
+    if process_output_event.read().next().is_none() {
+        return;
+    }
+    let key = |logical_key: Key, text: Option<&str>| KeyboardInput {
+        key_code: KeyCode::Unidentified(bevy::input::keyboard::NativeKeyCode::Unidentified),
+        logical_key,
+        state: ButtonState::Pressed,
+        text: text.map(Into::into),
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    };
+    keyboard_input_events.write_batch(
+        "Bevy"
+            .chars()
+            .map(|c| key(Key::Character(c.to_string().into()), Some(&c.to_string())))
+            .chain([key(Key::Enter, None)]),
+    );
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    consoles: Query<&Children, With<ProcessConsole>>,
+    children: Query<&Children>,
+    spans: Query<(&TextSpan, &TextColor)>,
+    texts: Query<&Text>,
+    mut completed_frames: Local<Option<u32>>,
+) {
+    if process_completed_event.read().next().is_some() {
+        *completed_frames = Some(0);
+    }
+    // Wait a frame for the last lines to be added to the console
+    let Some(frames) = completed_frames.as_mut() else {
+        return;
+    };
+    *frames += 1;
+    if *frames < 2 {
+        return;
+    }
+
+    // The console node has the log node, with a Text per line, followed by the input line
+    for console_children in consoles.iter() {
+        let (log, input_line) = (console_children[0], console_children[1]);
+        for line in children.get(log).into_iter().flatten() {
+            let line_spans: Vec<_> = children
+                .get(*line)
+                .into_iter()
+                .flatten()
+                .filter_map(|span| spans.get(*span).ok())
+                .map(|(span, color)| format!("{:?} in {}", span.0, color.0.to_srgba().to_hex()))
+                .collect();
+            println!("Console line: {}", line_spans.join(", "));
+        }
+        println!("Console input line: {:?}", texts.get(input_line).unwrap().0);
+    }
+    // Quit the app
+    std::process::exit(0);
+}
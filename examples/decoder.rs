@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LengthPrefixedDecoder, LocalCommand, NulDelimitedDecoder,
+    ProcessCompleted, ProcessOutputFrame,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let (nul, prefixed, stderr) = (
+        LocalCommand::new("printf").arg("first\\0multi\\nline\\0last"),
+        LocalCommand::new("printf").arg("\\0\\0\\0\\005hello\\0\\0\\0\\005world"),
+        LocalCommand::new("sh").args(["-c", "printf 'warning\\0error' >&2"]),
+    );
+    #[cfg(windows)]
+    let (nul, prefixed, stderr) = (
+        LocalCommand::new("powershell")
+            .args(["[Console]::Out.Write(\"first`0multi`nline`0last\")"]),
+        LocalCommand::new("powershell")
+            .args(["[Console]::Out.Write(\"`0`0`0`5hello`0`0`0`5world\")"]),
+        LocalCommand::new("powershell").args(["[Console]::Error.Write(\"warning`0error\")"]),
+    );
+
+    let nul = commands.spawn(nul.decoder(NulDelimitedDecoder)).id();
+    let prefixed = commands.spawn(prefixed.decoder(LengthPrefixedDecoder)).id();
+    let stderr = commands
+        .spawn(stderr.stderr_decoder(NulDelimitedDecoder))
+        .id();
+    println!(
+        "Spawned the commands as entities {nul:?} (NUL delimited), {prefixed:?} (length prefixed) \
+        and {stderr:?} (NUL delimited stderr)"
+    );
+}
+
+fn update(
+    mut process_output_frame_event: EventReader<ProcessOutputFrame>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut completed: Local<usize>,
+) {
+    for process_output in process_output_frame_event.read() {
+        println!(
+            "Output Frame ({:?}, {:?}): {:?}",
+            process_output.entity,
+            process_output.stream,
+            process_output.text()
+        );
+    }
+    *completed += process_completed_event.read().count();
+    if *completed == 3 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(unix)]
+use bevy::prelude::*;
+#[cfg(unix)]
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, DropPolicy, LocalCommand, Process, ProcessCompleted, ProcessOutput,
+};
+
+#[cfg(unix)]
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, (despawn_servers, update).chain())
+        .init_resource::<DetachedPids>()
+        .run();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("This example relies on the Unix `kill` command");
+}
+
+/// More detached servers than there are threads in the default task pools.
+#[cfg(unix)]
+const SERVERS: usize = 16;
+
+/// Marks the long-running commands, left running after their entity is despawned.
+#[cfg(unix)]
+#[derive(Component)]
+struct Server;
+
+/// The pids of the despawned servers, to clean up after ourselves.
+#[cfg(unix)]
+#[derive(Resource, Default)]
+struct DetachedPids(Vec<u32>);
+
+/// The command spawned once all servers are despawned.
+#[cfg(unix)]
+#[derive(Resource)]
+struct Client(Entity);
+
+#[cfg(unix)]
+fn startup(mut commands: Commands) {
+    for _ in 0..SERVERS {
+        commands.spawn((
+            LocalCommand::new("sh")
+                .args(["-c", "echo server; sleep 20"])
+                .drop_policy(DropPolicy::Detach),
+            Server,
+        ));
+    }
+    println!("Spawned {SERVERS} detached servers");
+}
+
+#[cfg(unix)]
+fn despawn_servers(
+    mut commands: Commands,
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut detached_pids: ResMut<DetachedPids>,
+    query: Query<&Process, With<Server>>,
+) {
+    for process_output in process_output_event.read() {
+        let Ok(process) = query.get(process_output.entity) else {
+            println!("Output: {}", process_output.all());
+            continue;
+        };
+        println!(
+            "Despawning server {:?} with pid {}",
+            process_output.entity,
+            process.id()
+        );
+        detached_pids.0.push(process.id());
+        commands.entity(process_output.entity).despawn();
+
+        if detached_pids.0.len() == SERVERS {
+            // The servers still hold their output open, which must not prevent other commands
+            // from being read
+            let id = commands.spawn(LocalCommand::new("echo").arg("hi")).id();
+            commands.insert_resource(Client(id));
+            println!("Spawned the command as entity {id:?}");
+        }
+    }
+}
+
+#[cfg(unix)]
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    detached_pids: Res<DetachedPids>,
+    client: Option<Res<Client>>,
+) {
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!(
+            "Command {:?} completed (Success - {})",
+            process_completed.entity,
+            process_completed.exit_status.success()
+        );
+        // Only the client completes, the servers are still running
+        assert_eq!(
+            Some(process_completed.entity),
+            client.as_ref().map(|client| client.0)
+        );
+        assert!(process_completed.exit_status.success());
+        // Clean up after ourselves
+        for pid in &detached_pids.0 {
+            std::process::Command::new("kill")
+                .arg(pid.to_string())
+                .status()
+                .unwrap();
+        }
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(unix)]
+use bevy::prelude::*;
+#[cfg(unix)]
+use bevy_local_commands::{BevyLocalCommandsPlugin, DropPolicy, LocalCommand, Process};
+
+#[cfg(unix)]
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, (check_despawned, despawn_started).chain())
+        .run();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("This example relies on the Unix `kill` command");
+}
+
+/// Marks the command that should keep running after its entity is despawned.
+#[cfg(unix)]
+#[derive(Component)]
+struct Detached;
+
+/// Keeps track of the process of a despawned entity.
+#[cfg(unix)]
+#[derive(Component)]
+struct Despawned {
+    pid: u32,
+    detached: bool,
+}
+
+#[cfg(unix)]
+fn startup(mut commands: Commands) {
+    let killed = commands.spawn(LocalCommand::new("sleep").arg("100")).id();
+    let detached = commands
+        .spawn((
+            LocalCommand::new("sleep")
+                .arg("100")
+                .drop_policy(DropPolicy::Detach),
+            Detached,
+        ))
+        .id();
+    println!("Spawned the commands as entities {killed:?} and {detached:?} (detached)");
+}
+
+#[cfg(unix)]
+fn despawn_started(
+    mut commands: Commands,
+    query: Query<(Entity, &Process, Has<Detached>), Added<Process>>,
+) {
+    for (entity, process, detached) in query.iter() {
+        println!("Despawning {entity:?} with pid {}", process.id());
+        commands.entity(entity).despawn();
+        commands.spawn(Despawned {
+            pid: process.id(),
+            detached,
+        });
+    }
+}
+
+#[cfg(unix)]
+fn check_despawned(mut commands: Commands, query: Query<(Entity, &Despawned)>) {
+    for (entity, despawned) in query.iter() {
+        let pid = despawned.pid.to_string();
+        let running = std::process::Command::new("kill")
+            .args(["-0", &pid])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap()
+            .success();
+        println!(
+            "Process {pid} running: {running} (Detached - {})",
+            despawned.detached
+        );
+        assert_eq!(running, despawned.detached);
+        if despawned.detached {
+            // Clean up after ourselves
+            std::process::Command::new("kill")
+                .arg(&pid)
+                .status()
+                .unwrap();
+            // Quit the app
+            std::process::exit(0);
+        }
+        commands.entity(entity).despawn();
+    }
+}
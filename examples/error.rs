@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, ProcessError};
+use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, ProcessError, ProcessErrorInfo};
 
 fn main() {
     App::new()
@@ -14,11 +14,21 @@ fn startup(mut commands: Commands) {
 }
 
 fn update(mut process_error: EventReader<ProcessError>) {
-    for error in process_error.read() {
+    if let Some(error) = process_error.read().next() {
         println!(
             "Error running command ({:?}): {:?}",
             error.entity, error.info
         );
+        if let ProcessErrorInfo::FailedToStart {
+            kind,
+            program,
+            message,
+            ..
+        } = &error.info
+        {
+            assert_eq!(*kind, std::io::ErrorKind::NotFound);
+            println!("Failed to start {program:?}: {message}");
+        }
         // Quit the app
         std::process::exit(0);
     }
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, Process, ProcessCompleted, ProcessOutput,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        // Ask the commands to stop after 1s
+        .add_systems(Update, stop.run_if(on_timer(Duration::from_secs(1))))
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the commands based on the OS
+    #[cfg(not(windows))]
+    let (cmd_graceful, cmd_stubborn) = (
+        LocalCommand::new("sh").args([
+            "-c",
+            "trap 'echo Cleaning up && exit 0' TERM; echo Running; while true; do sleep 0.1; done",
+        ]),
+        LocalCommand::new("sh").args([
+            "-c",
+            "trap 'echo Ignoring SIGTERM' TERM; echo Running; while true; do sleep 0.1; done",
+        ]),
+    );
+    #[cfg(windows)]
+    let (cmd_graceful, cmd_stubborn) = (
+        LocalCommand::new("powershell").args(["echo Running; sleep 100"]),
+        LocalCommand::new("powershell").args(["echo Running; sleep 100"]),
+    );
+
+    let graceful = commands.spawn(cmd_graceful).id();
+    let stubborn = commands.spawn(cmd_stubborn).id();
+    println!("Spawned the commands as entities {graceful:?} and {stubborn:?}");
+}
+
+fn stop(mut active_processes: Query<(Entity, &mut Process)>, mut stopped: Local<bool>) {
+    if *stopped {
+        return;
+    }
+    for (entity, mut process) in active_processes.iter_mut() {
+        println!("Stopping {entity:?} with a 1s grace period");
+        process.stop_gracefully(Duration::from_secs(1)).unwrap();
+        *stopped = true;
+    }
+}
+
+fn update(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut completed: Local<usize>,
+) {
+    for process_output in process_output_event.read() {
+        for line in process_output.lines() {
+            println!("Output Line ({:?}): {line}", process_output.entity);
+        }
+    }
+    for process_completed in process_completed_event.read() {
+        println!(
+            "Command {:?} completed (Termination - {:?})",
+            process_completed.entity, process_completed.termination
+        );
+        *completed += 1;
+    }
+    if *completed == 2 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, OutputHistory, ProcessCompleted};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args([
+        "-c",
+        "for i in 1 2 3 4 5 6 7 8; do echo \"Line $i\"; done; echo 'Failed!' >&2",
+    ]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell")
+        .args(["1..8 | ForEach-Object { \"Line $_\" }; [Console]::Error.WriteLine('Failed!')"]);
+
+    // Only keep the last 5 lines
+    let id = commands.spawn((cmd, OutputHistory::new(5))).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    query: Query<&OutputHistory>,
+) {
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        let history = query.get(process_completed.entity).unwrap();
+        println!(
+            "Last {} lines ({} bytes, {} dropped):",
+            history.len(),
+            history.bytes(),
+            history.dropped_lines()
+        );
+        for line in history.lines() {
+            let age = line.timestamp.elapsed().unwrap_or_default();
+            println!("  [{:?}, {age:?} ago] {}", line.stream, line.text);
+        }
+        // Quit the app
+        std::process::exit(0);
+    }
+}
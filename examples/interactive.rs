@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, Interactive, LocalCommand, ProcessCompleted, ReplyReceived, Terminator,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS, each prompt echoes a marker once it is done
+    #[cfg(not(windows))]
+    let (cmd, prompts) = (
+        LocalCommand::new("sh"),
+        [
+            "echo $((6 * 7)); echo END",
+            "ls -d / /tmp; echo END",
+            "exit",
+        ],
+    );
+    #[cfg(windows)]
+    let (cmd, prompts) = (
+        LocalCommand::new("powershell").args(["-NoLogo", "-NoProfile", "-Command", "-"]),
+        ["6 * 7; 'END'", "Get-Location; 'END'", "exit"],
+    );
+
+    let mut interactive = Interactive::new(Terminator::Line("END".to_string()));
+    for prompt in prompts {
+        let request_id = interactive.send(prompt);
+        println!("Queued prompt {request_id}: {prompt}");
+    }
+    let id = commands.spawn((cmd, interactive)).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut reply_events: EventReader<ReplyReceived>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+) {
+    for reply in reply_events.read() {
+        println!(
+            "Reply to prompt {} ({:?}):\n{}",
+            reply.request_id,
+            reply.entity,
+            reply.output.trim_end()
+        );
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
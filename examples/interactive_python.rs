@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, Interactive, LocalCommand, ProcessCompleted, ReplyReceived, Terminator,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Python writes its prompt to stderr, merge it into stdout with the shell
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args(["-c", "python3 -i -q -u 2>&1"]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("cmd").args(["/C", "python -i -q -u 2>&1"]);
+
+    // The reply to a prompt ends with the next `>>> ` prompt, which is not followed by a newline
+    let mut interactive = Interactive::new(Terminator::Line(">>>".to_string())).wait_for_prompt();
+    for prompt in ["6 * 7", "print('Hello\\nworld')", "exit()"] {
+        let request_id = interactive.send(prompt);
+        println!("Queued prompt {request_id}: {prompt}");
+    }
+    let id = commands.spawn((cmd, interactive)).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut reply_events: EventReader<ReplyReceived>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+) {
+    for reply in reply_events.read() {
+        println!(
+            "Reply to prompt {} ({:?}):\n{}",
+            reply.request_id,
+            reply.entity,
+            reply.output.trim_end()
+        );
+        match reply.request_id {
+            1 => assert_eq!(reply.output.trim_end(), "42"),
+            2 => assert_eq!(reply.output.trim_end(), "Hello\nworld"),
+            _ => unreachable!("exit() has no reply"),
+        }
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
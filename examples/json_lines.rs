@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, JsonLinesOutput, JsonLinesParseError, JsonLinesPlugin, LocalCommand,
+    ProcessCompleted, ProcessMessage,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Progress {
+    step: u32,
+    message: String,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((
+            MinimalPlugins,
+            BevyLocalCommandsPlugin,
+            JsonLinesPlugin::<Progress>::default(),
+        ))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    let script = r#"echo '{"step": 1, "message": "Loading"}'
+echo 'Not JSON'
+echo '{"step": 2, "message": "Done"}'"#;
+
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args(["-c", script]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell").args([script]);
+
+    let id = commands
+        .spawn((cmd, JsonLinesOutput::<Progress>::new()))
+        .id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut process_message_event: EventReader<ProcessMessage<Progress>>,
+    mut parse_error_event: EventReader<JsonLinesParseError>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+) {
+    for process_message in process_message_event.read() {
+        println!(
+            "Step {} ({:?}): {}",
+            process_message.message.step, process_message.entity, process_message.message.message
+        );
+    }
+    for parse_error in parse_error_event.read() {
+        println!(
+            "Failed to parse {:?} ({:?}): {}",
+            parse_error.line, parse_error.entity, parse_error.error
+        );
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
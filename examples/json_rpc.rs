@@ -0,0 +1,85 @@
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, JsonRpc, JsonRpcFraming, JsonRpcNotification, JsonRpcResponse,
+    JsonRpcTimeoutEvent, LocalCommand, Process,
+};
+use serde_json::{json, Value};
+
+fn main() {
+    // The example starts itself as the JSON-RPC server
+    if std::env::args().any(|arg| arg == "--server") {
+        return server();
+    }
+
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, (send_requests, update))
+        .run();
+}
+
+/// Answers "add" requests and ignores every other request.
+fn server() {
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        let request: Value = serde_json::from_str(&line.unwrap()).unwrap();
+        if request["method"] == "add" {
+            let sum =
+                request["params"][0].as_i64().unwrap() + request["params"][1].as_i64().unwrap();
+            let notification = json!({ "jsonrpc": "2.0", "method": "log", "params": format!("Adding {}", request["params"]) });
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": sum });
+            writeln!(stdout, "{notification}\n{response}").unwrap();
+            stdout.flush().unwrap();
+        }
+    }
+}
+
+fn startup(mut commands: Commands) {
+    let id = commands
+        .spawn((
+            LocalCommand::new(std::env::current_exe().unwrap()).arg("--server"),
+            JsonRpc::new(JsonRpcFraming::Lines).request_timeout(Some(Duration::from_secs(1))),
+        ))
+        .id();
+    println!("Spawned the JSON-RPC server as entity {id:?}");
+}
+
+fn send_requests(mut query: Query<(&mut JsonRpc, &mut Process), Added<Process>>) {
+    for (mut rpc, mut process) in query.iter_mut() {
+        let add = rpc.request(&mut process, "add", json!([2, 3])).unwrap();
+        let sleep = rpc.request(&mut process, "sleep", Value::Null).unwrap();
+        println!("Sent requests {add} (add) and {sleep} (sleep)");
+    }
+}
+
+fn update(
+    mut response_events: EventReader<JsonRpcResponse>,
+    mut notification_events: EventReader<JsonRpcNotification>,
+    mut timeout_events: EventReader<JsonRpcTimeoutEvent>,
+    mut done: Local<usize>,
+) {
+    for notification in notification_events.read() {
+        println!(
+            "Notification {}: {}",
+            notification.method, notification.params
+        );
+    }
+    for response in response_events.read() {
+        println!(
+            "Response to {} ({}): {:?}",
+            response.id, response.method, response.result
+        );
+        *done += 1;
+    }
+    for timeout in timeout_events.read() {
+        println!("Request {} ({}) timed out", timeout.id, timeout.method);
+        *done += 1;
+    }
+    if *done == 2 {
+        // Quit the app, the server exits once its stdin is closed
+        std::process::exit(0);
+    }
+}
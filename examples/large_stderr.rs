@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, OutputStream, ProcessCompleted, ProcessOutput,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Write far more than a pipe buffer holds to stderr before writing to stdout
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh")
+        .args(["-c", "head -c 200000 /dev/zero | tr '\\0' x >&2; echo done"]);
+    #[cfg(windows)]
+    let cmd =
+        LocalCommand::new("powershell").args(["[Console]::Error.Write('x' * 200000); echo done"]);
+
+    let id = commands.spawn(cmd).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut stderr_len: Local<usize>,
+    mut stdout: Local<String>,
+) {
+    for process_output in process_output_event.read() {
+        for line in process_output.lines() {
+            match process_output.stream {
+                OutputStream::Stdout => stdout.push_str(line),
+                OutputStream::Stderr => *stderr_len += line.len(),
+            }
+        }
+    }
+    if let Some(process_completed) = process_completed_event.read().last() {
+        println!(
+            "Command {:?} completed after writing {} bytes to stderr and {:?} to stdout",
+            process_completed.entity, *stderr_len, *stdout
+        );
+        assert!(process_completed.exit_status.success());
+        assert_eq!(*stderr_len, 200000);
+        assert_eq!(stdout.trim(), "done");
+        // Quit the app
+        std::process::exit(0);
+    }
+}
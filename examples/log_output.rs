@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, LogOutput, ProcessCompleted};
+use bevy_log::{Level, LogPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            MinimalPlugins,
+            LogPlugin {
+                // Show the debug output of commands, on top of the info logs
+                filter: "info,bevy_local_commands::output=debug".to_string(),
+                ..default()
+            },
+            BevyLocalCommandsPlugin,
+        ))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the commands based on the OS
+    #[cfg(not(windows))]
+    let (build, test) = (
+        LocalCommand::new("sh").args(["-c", "echo 'Compiling assets'; echo 'Missing texture' >&2"]),
+        LocalCommand::new("sh").args(["-c", "echo 'Running 3 tests'; echo 'Test failed' >&2"]),
+    );
+    #[cfg(windows)]
+    let (build, test) = (
+        LocalCommand::new("powershell")
+            .args(["echo 'Compiling assets'; [Console]::Error.WriteLine('Missing texture')"]),
+        LocalCommand::new("powershell")
+            .args(["echo 'Running 3 tests'; [Console]::Error.WriteLine('Test failed')"]),
+    );
+
+    // stdout at DEBUG and stderr at WARN
+    commands.spawn((build, LogOutput::new().name("build")));
+    // stdout at INFO and stderr at ERROR
+    commands.spawn((
+        test,
+        LogOutput::new()
+            .name("test")
+            .stdout_level(Some(Level::INFO))
+            .stderr_level(Some(Level::ERROR)),
+    ));
+}
+
+fn update(mut process_completed_event: EventReader<ProcessCompleted>, mut completed: Local<usize>) {
+    *completed += process_completed_event.read().count();
+    if *completed == 2 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
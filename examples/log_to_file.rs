@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, LogToFile, ProcessCompleted};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args([
+        "-c",
+        "for i in 1 2 3 4 5 6; do echo \"Line $i\"; done; echo 'Some error' >&2",
+    ]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell")
+        .args(["1..6 | ForEach-Object { \"Line $_\" }; [Console]::Error.WriteLine('Some error')"]);
+
+    let template = std::env::temp_dir()
+        .join("bevy_local_commands")
+        .join("{program}-{pid}-{start}.log");
+    let log = LogToFile::new(template.to_string_lossy())
+        .rotate(64, 2)
+        .timestamps(true);
+    let id = commands.spawn((cmd, log)).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(mut process_completed_event: EventReader<ProcessCompleted>, logs: Query<&LogToFile>) {
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        let path = logs
+            .get(process_completed.entity)
+            .unwrap()
+            .paths()
+            .next()
+            .unwrap();
+        for rotated in ["", ".1", ".2"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(rotated);
+            println!("{}:", file.to_string_lossy());
+            let contents = std::fs::read_to_string(&file).unwrap_or_default();
+            print!("{contents}");
+            std::fs::remove_file(&file).ok();
+        }
+        // Quit the app
+        std::process::exit(0);
+    }
+}
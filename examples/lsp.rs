@@ -0,0 +1,130 @@
+use std::io::{BufRead, Read, Write};
+
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, JsonRpc, JsonRpcNotification, JsonRpcResponse, LanguageServer,
+    LanguageServerExited, LanguageServerReady, LocalCommand, Process, ProcessCompleted,
+};
+use serde_json::{json, Value};
+
+fn main() {
+    // The example starts itself as a fake language server
+    if std::env::args().any(|arg| arg == "--fake-server") {
+        return fake_server();
+    }
+
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+/// Answers the handshake and hover requests, and exits on the exit notification.
+fn fake_server() {
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout();
+    let mut send = |message: Value| {
+        let body = message.to_string();
+        write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        stdout.flush().unwrap();
+    };
+
+    loop {
+        // Read the headers, then the body
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            stdin.read_line(&mut header).unwrap();
+            match header.trim_end().split_once(": ") {
+                Some(("Content-Length", value)) => length = value.parse().unwrap(),
+                _ if header.trim_end().is_empty() => break,
+                _ => {},
+            }
+        }
+        let mut body = vec![0; length];
+        stdin.read_exact(&mut body).unwrap();
+        let message: Value = serde_json::from_slice(&body).unwrap();
+
+        let id = message["id"].clone();
+        match message["method"].as_str().unwrap() {
+            "initialize" => send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "capabilities": { "hoverProvider": true },
+                    "serverInfo": { "name": "fake-server" },
+                },
+            })),
+            "initialized" => send(json!({
+                "jsonrpc": "2.0",
+                "method": "window/logMessage",
+                "params": { "type": 3, "message": "Fake server initialized" },
+            })),
+            "textDocument/hover" => send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": { "contents": "fn main()" },
+            })),
+            "shutdown" => send(json!({ "jsonrpc": "2.0", "id": id, "result": null })),
+            "exit" => return,
+            method => eprintln!("Unknown method {method}"),
+        }
+    }
+}
+
+fn startup(mut commands: Commands) {
+    let id = commands
+        .spawn((
+            LocalCommand::new(std::env::current_exe().unwrap()).arg("--fake-server"),
+            LanguageServer::new("file:///tmp/project")
+                .capabilities(json!({ "textDocument": { "hover": {} } })),
+        ))
+        .id();
+    println!("Spawned the language server as entity {id:?}");
+}
+
+fn update(
+    mut ready_events: EventReader<LanguageServerReady>,
+    mut notification_events: EventReader<JsonRpcNotification>,
+    mut response_events: EventReader<JsonRpcResponse>,
+    mut exited_events: EventReader<LanguageServerExited>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut query: Query<(&mut LanguageServer, &mut JsonRpc, &mut Process)>,
+) {
+    for ready in ready_events.read() {
+        println!(
+            "Language server {} ready with capabilities {}",
+            ready.server_info["name"], ready.capabilities
+        );
+        let (_, mut rpc, mut process) = query.get_mut(ready.entity).unwrap();
+        let params = json!({
+            "textDocument": { "uri": "file:///tmp/project/main.rs" },
+            "position": { "line": 0, "character": 3 },
+        });
+        rpc.request(&mut process, "textDocument/hover", params)
+            .unwrap();
+    }
+    for notification in notification_events.read() {
+        println!(
+            "Notification {}: {}",
+            notification.method, notification.params
+        );
+    }
+    for response in response_events.read() {
+        println!("Response to {}: {:?}", response.method, response.result);
+        if response.method == "textDocument/hover" {
+            let (mut language_server, mut rpc, mut process) =
+                query.get_mut(response.entity).unwrap();
+            language_server.shutdown(&mut rpc, &mut process).unwrap();
+        }
+    }
+    for exited in exited_events.read() {
+        println!("Language server {:?} exited", exited.entity);
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
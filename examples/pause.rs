@@ -0,0 +1,74 @@
+#[cfg(unix)]
+use std::time::Duration;
+
+#[cfg(unix)]
+use bevy::{prelude::*, time::common_conditions::on_timer};
+#[cfg(unix)]
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, Process, ProcessCompleted, ProcessOutput,
+};
+
+#[cfg(unix)]
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        // Pause or resume the command every 1.5s
+        .add_systems(
+            Update,
+            toggle_pause.run_if(on_timer(Duration::from_millis(1500))),
+        )
+        .run();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("Pausing processes is only supported on Unix");
+}
+
+#[cfg(unix)]
+fn startup(mut commands: Commands) {
+    let cmd = LocalCommand::new("sh").args([
+        "-c",
+        "for i in 1 2 3 4 5 6; do echo Tick $i && sleep 0.5; done",
+    ]);
+
+    let id = commands.spawn(cmd).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+#[cfg(unix)]
+fn toggle_pause(mut active_processes: Query<(Entity, &mut Process, &LocalCommand)>) {
+    for (entity, mut process, local_command) in active_processes.iter_mut() {
+        println!("State of {entity:?}: {:?}", local_command.get_state());
+        if process.is_paused() {
+            println!("Resuming {entity:?}");
+            process.resume().unwrap();
+        } else {
+            println!("Pausing {entity:?}");
+            process.pause().unwrap();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn update(
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+) {
+    for process_output in process_output_event.read() {
+        for line in process_output.lines() {
+            println!("Output Line ({:?}): {line}", process_output.entity);
+        }
+    }
+    if let Some(process_completed) = process_completed_event.read().last() {
+        println!(
+            "Command {:?} completed (Success - {})",
+            process_completed.entity,
+            process_completed.exit_status.success()
+        );
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(unix)]
+use bevy::prelude::*;
+#[cfg(unix)]
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, Process, ProcessCompleted, ProcessOutputBytes, PtySize,
+};
+
+#[cfg(unix)]
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("Pseudo-terminals are only supported on Unix");
+}
+
+#[cfg(unix)]
+fn startup(mut commands: Commands) {
+    let cmd = LocalCommand::new("sh")
+        .args([
+            "-c",
+            "test -t 1 && echo \"Running in a terminal of size $(stty size)\"; read LINE; echo \"Resized to $(stty size)\"",
+        ])
+        .pty(PtySize::new(24, 80));
+    let id = commands.spawn(cmd).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+#[cfg(unix)]
+fn update(
+    mut process_output_bytes_event: EventReader<ProcessOutputBytes>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut active_processes: Query<&mut Process>,
+    mut resized: Local<bool>,
+) {
+    for process_output in process_output_bytes_event.read() {
+        // The raw terminal output, which includes the echo of the input and \r\n line endings
+        print!("{}", String::from_utf8_lossy(process_output.bytes()));
+        if *resized {
+            continue;
+        }
+        // Resize the terminal, then let the command continue
+        let mut process = active_processes.get_mut(process_output.entity).unwrap();
+        process.resize_pty(PtySize::new(40, 120)).unwrap();
+        process.println("").unwrap();
+        *resized = true;
+    }
+    if let Some(process_completed) = process_completed_event.read().next() {
+        println!("{:?}", process_completed);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
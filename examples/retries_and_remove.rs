@@ -43,7 +43,7 @@ fn update(
         assert!(components.3.is_some());
         assert!(components.4.is_some());
     }
-    for process_completed in process_completed_event.read() {
+    if let Some(process_completed) = process_completed_event.read().next() {
         println!("{:?}", process_completed);
         let components = query.get(process_completed.entity).unwrap();
         assert!(components.1.is_none());
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    Backoff, BevyLocalCommandsPlugin, LocalCommand, ProcessCompleted, Retry, RetryEvent,
+    RetryPolicy,
+};
+use std::time::Duration;
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args(["-c", "echo Failing && THIS SHOULD FAIL"]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("cmd").args(["/C", "echo Failing && THIS SHOULD FAIL"]);
+
+    let retry = RetryPolicy::new(3)
+        .backoff(Backoff::Exponential {
+            initial: Duration::from_millis(250),
+            multiplier: 2.0,
+        })
+        .max_delay(Duration::from_millis(800))
+        .jitter(0.1);
+
+    let id = commands.spawn((cmd, Retry::Policy(retry))).id();
+    println!("Spawned the command as entity {id:?} with 3 retries and exponential backoff");
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    query: Query<&LocalCommand, With<Retry>>,
+    mut retry_events: EventReader<RetryEvent>,
+) {
+    for retry_event in retry_events.read() {
+        println!(
+            "Retry event triggered, next attempt in {:?} ({} left)",
+            retry_event.delay, retry_event.retries_left
+        );
+    }
+    if let Some(process_completed) = process_completed_event.read().last() {
+        if query.get(process_completed.entity).is_err() {
+            println!("Retry component removed from entity, exiting");
+            std::process::exit(0);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, ProcessCompleted, Retry, RetryCondition, RetryEvent,
+    RetryPolicy,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the commands based on the OS
+    #[cfg(not(windows))]
+    let (cmd_bad_input, cmd_unavailable) = (
+        LocalCommand::new("sh").args(["-c", "echo Bad input && exit 2"]),
+        LocalCommand::new("sh").args(["-c", "echo Unavailable, try again >&2 && exit 3"]),
+    );
+    #[cfg(windows)]
+    let (cmd_bad_input, cmd_unavailable) = (
+        LocalCommand::new("cmd").args(["/C", "echo Bad input && exit 2"]),
+        LocalCommand::new("cmd").args(["/C", "echo Unavailable, try again 1>&2 && exit 3"]),
+    );
+
+    // Exit code 2 is not in the retryable exit codes, so the command is not retried
+    let bad_input = commands
+        .spawn((
+            cmd_bad_input,
+            Retry::Policy(RetryPolicy::new(2).retry_if(RetryCondition::ExitCodes(vec![1, 3]))),
+        ))
+        .id();
+    // The command asks to be retried in its output
+    let unavailable = commands
+        .spawn((
+            cmd_unavailable,
+            Retry::Policy(
+                RetryPolicy::new(2).retry_if(RetryCondition::custom(|context| {
+                    context.stderr.contains("try again")
+                })),
+            ),
+        ))
+        .id();
+    println!("Spawned the commands as entities {bad_input:?} and {unavailable:?}");
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut retry_events: EventReader<RetryEvent>,
+    mut completed: Local<usize>,
+) {
+    for retry_event in retry_events.read() {
+        println!("Retry event triggered: {:?}", retry_event);
+    }
+    for process_completed in process_completed_event.read() {
+        println!(
+            "Command {:?} completed (Exit code - {:?})",
+            process_completed.entity,
+            process_completed.exit_status.code()
+        );
+        *completed += 1;
+    }
+    if *completed == 2 {
+        // Quit the app
+        std::process::exit(0);
+    }
+}
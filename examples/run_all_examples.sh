@@ -9,4 +9,30 @@ cargo run --example retries_and_delay_and_cleanup
 cargo run --example retries_and_delay
 cargo run --example retries_and_remove
 cargo run --example simple
-cargo run --example simple_chain
\ No newline at end of file
+cargo run --example simple_chain
+cargo run --example stderr
+cargo run --example large_stderr
+cargo run --example stdio
+cargo run --example timeout
+cargo run --example graceful_stop
+cargo run --example pause
+cargo run --example drop_policy
+cargo run --example detached_despawn
+cargo run --example retries_with_backoff
+cargo run --example retry_conditions
+cargo run --example bytes
+cargo run --example decoder
+cargo run --example json_lines --features serde
+cargo run --example json_rpc --features serde
+cargo run --example lsp --features serde
+cargo run --example interactive
+cargo run --example pty
+cargo run --example ansi
+cargo run --example console --features ui
+cargo run --example history
+cargo run --example capture
+cargo run --example log_to_file
+cargo run --example log_output
+cargo run --example tracing_spans
+cargo run --example timing
+cargo run --example interactive_python
\ No newline at end of file
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_local_commands::{BevyLocalCommandsPlugin, LocalCommand, ProcessCompleted, StdioMode};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn output_path() -> std::path::PathBuf {
+    std::env::temp_dir().join("bevy_local_commands_stdio_example.txt")
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args(["-c", "echo Written to a file && echo Discarded >&2"]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell")
+        .args(["echo 'Written to a file'; [Console]::Error.WriteLine('Discarded')"]);
+
+    let cmd = cmd
+        .stdin(StdioMode::Null)
+        .stdout(StdioMode::File(output_path()))
+        .stderr(StdioMode::Null);
+
+    let id = commands.spawn(cmd).id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(mut process_completed_event: EventReader<ProcessCompleted>) {
+    if let Some(process_completed) = process_completed_event.read().last() {
+        println!(
+            "Command {:?} completed (Success - {})",
+            process_completed.entity,
+            process_completed.exit_status.success()
+        );
+        let output = std::fs::read_to_string(output_path()).unwrap();
+        print!("File content: {output}");
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, LocalCommand, LocalCommandDone, LocalCommandState, ProcessCompleted,
+    Retry, RetryEvent, Timeout, TimeoutEvent,
+};
+use std::time::Duration;
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sleep").arg("100");
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell").args(["sleep 100"]);
+
+    let id = commands
+        .spawn((
+            cmd,
+            Timeout::After(Duration::from_secs(1)),
+            Retry::Attempts(1),
+        ))
+        .id();
+    println!("Spawned the command as entity {id:?} with a 1s timeout and 1 retry");
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut timeout_events: EventReader<TimeoutEvent>,
+    mut retry_events: EventReader<RetryEvent>,
+    query: Query<&LocalCommand>,
+) {
+    for timeout_event in timeout_events.read() {
+        println!("Timeout event triggered: {:?}", timeout_event);
+    }
+    for retry_event in retry_events.read() {
+        println!("Retry event triggered: {:?}", retry_event);
+    }
+    if let Some(process_completed) = process_completed_event.read().last() {
+        let local_command = query.get(process_completed.entity).unwrap();
+        println!(
+            "Command {:?} completed (State - {:?})",
+            process_completed.entity,
+            local_command.get_state()
+        );
+        assert_eq!(
+            local_command.get_state(),
+            &LocalCommandState::Done(LocalCommandDone::TimedOut)
+        );
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, Delay, LocalCommand, ProcessCompleted, ProcessTiming, Retry,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((MinimalPlugins, BevyLocalCommandsPlugin))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the command based on the OS
+    #[cfg(not(windows))]
+    let cmd = LocalCommand::new("sh").args(["-c", "sleep 0.5; exit 1"]);
+    #[cfg(windows)]
+    let cmd = LocalCommand::new("powershell").args(["Start-Sleep -Milliseconds 500; exit 1"]);
+
+    let id = commands
+        .spawn((
+            cmd,
+            Delay::Fixed(Duration::from_millis(200)),
+            Retry::Attempts(1),
+        ))
+        .id();
+    println!("Spawned the command as entity {id:?}");
+}
+
+fn update(
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    query: Query<&ProcessTiming>,
+) {
+    if let Some(completed) = process_completed_event.read().next() {
+        println!(
+            "Attempt {} ran for {:?} after waiting {:?} (from {:?} to {:?})",
+            completed.attempt,
+            completed.duration,
+            completed.delay,
+            completed.spawned_at,
+            completed.completed_at
+        );
+        // The first attempt failed and was retried
+        assert_eq!(completed.attempt, 2);
+        assert!(completed.completed_at >= completed.spawned_at);
+        // Leave some slack, as sleep durations are not exact on every platform
+        assert!(completed.duration >= Duration::from_millis(450));
+        assert!(!completed.delay.is_zero());
+
+        // The component has the same timing
+        let timing = query.get(completed.entity).unwrap();
+        assert_eq!(timing.duration(), completed.duration);
+        assert_eq!(timing.completed_at(), Some(completed.completed_at));
+
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_local_commands::{
+    BevyLocalCommandsPlugin, Chain, ChainCompletedEvent, Delay, LocalCommand, Retry,
+};
+use bevy_log::LogPlugin;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            MinimalPlugins,
+            // Logs show the spans they are in, e.g. `command{program="sh" ...}:attempt{attempt=2 ...}`
+            // Run with `--features bevy/trace_chrome` to also write a chrome tracing file
+            LogPlugin::default(),
+            BevyLocalCommandsPlugin,
+        ))
+        .add_systems(Startup, startup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    // Choose the commands based on the OS
+    #[cfg(not(windows))]
+    let chain = Chain::new(vec![
+        LocalCommand::new("sh").args(["-c", "sleep 0.2"]),
+        LocalCommand::new("sh").args(["-c", "exit 1"]), // Failure, retried once
+        LocalCommand::new("sh").args(["-c", "echo 'Never runs'"]),
+    ]);
+    #[cfg(windows)]
+    let chain = Chain::new(vec![
+        LocalCommand::new("powershell").args(["Start-Sleep -Milliseconds 200"]),
+        LocalCommand::new("powershell").args(["exit 1"]), // Failure, retried once
+        LocalCommand::new("powershell").args(["echo 'Never runs'"]),
+    ]);
+    commands.spawn((
+        chain,
+        Retry::Attempts(1),
+        Delay::Fixed(Duration::from_millis(100)),
+    ));
+}
+
+fn update(mut chain_completed_event: EventReader<ChainCompletedEvent>) {
+    if let Some(completed) = chain_completed_event.read().next() {
+        assert!(!completed.success);
+        // Quit the app
+        std::process::exit(0);
+    }
+}
@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+use std::process::ExitStatus;
+
+use bevy::prelude::*;
+
+use crate::{
+    process::Process, OutputStream, ProcessCompleted, ProcessOutput, ProcessOutputBytes,
+    Termination,
+};
+
+/// Collects the whole output of the process, sent as a [`ProcessFinishedWithOutput`] event once
+/// it completes, like [`std::process::Command::output`].
+///
+/// Each stream keeps up to `max_bytes` (1 MiB by default), the rest of its output is dropped
+/// and the event is flagged as truncated. Output sent as frames (see
+/// [`LocalCommand::decoder`](crate::LocalCommand::decoder)) is not collected. When the process
+/// is retried, only the output of the last attempt is kept.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{CaptureOutput, LocalCommand, ProcessFinishedWithOutput};
+///
+/// fn get_commit(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("git").args(["rev-parse", "HEAD"]),
+///         CaptureOutput::new().max_bytes(1024),
+///     ));
+/// }
+///
+/// fn print_commit(mut finished_events: EventReader<ProcessFinishedWithOutput>) {
+///     for finished in finished_events.read() {
+///         if finished.exit_status.success() {
+///             println!("Commit {}", finished.stdout_text().trim());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Component)]
+pub struct CaptureOutput {
+    max_bytes: usize,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    truncated: bool,
+}
+
+impl CaptureOutput {
+    pub fn new() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Sets the maximum number of bytes kept for each of stdout and stderr.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    fn append(&mut self, stream: OutputStream, bytes: &[u8]) {
+        let buffer = match stream {
+            OutputStream::Stdout => &mut self.stdout,
+            OutputStream::Stderr => &mut self.stderr,
+        };
+        let kept = bytes.len().min(self.max_bytes.saturating_sub(buffer.len()));
+        buffer.extend_from_slice(&bytes[..kept]);
+        self.truncated |= kept < bytes.len();
+    }
+
+    fn reset(&mut self) {
+        self.stdout.clear();
+        self.stderr.clear();
+        self.truncated = false;
+    }
+}
+
+impl Default for CaptureOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The whole output of a completed [`CaptureOutput`] process.
+///
+/// Sent right after its [`ProcessCompleted`] event.
+#[derive(Debug, Clone, Event)]
+pub struct ProcessFinishedWithOutput {
+    pub entity: Entity,
+    pub exit_status: ExitStatus,
+    pub termination: Option<Termination>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Whether some of the output was dropped because of the size cap.
+    pub truncated: bool,
+}
+
+impl ProcessFinishedWithOutput {
+    /// The stdout as text, replacing invalid UTF-8.
+    pub fn stdout_text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stdout)
+    }
+
+    /// The stderr as text, replacing invalid UTF-8.
+    pub fn stderr_text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stderr)
+    }
+}
+
+/// Collect the output of [`CaptureOutput`] processes, and send it once they complete.
+pub(crate) fn capture_process_output(
+    mut query: Query<(&mut CaptureOutput, Option<Ref<Process>>)>,
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_output_bytes_event: EventReader<ProcessOutputBytes>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+    mut finished_events: EventWriter<ProcessFinishedWithOutput>,
+) {
+    // Forget the output of previous attempts
+    for (mut capture, process) in query.iter_mut() {
+        if process.is_some_and(|process| process.is_added()) {
+            capture.reset();
+        }
+    }
+
+    for output in process_output_event.read() {
+        if let Ok((mut capture, _)) = query.get_mut(output.entity) {
+            capture.append(output.stream, output.output.as_bytes());
+        }
+    }
+    for output in process_output_bytes_event.read() {
+        if let Ok((mut capture, _)) = query.get_mut(output.entity) {
+            capture.append(output.stream, output.bytes());
+        }
+    }
+
+    for completed in process_completed_event.read() {
+        if let Ok((mut capture, _)) = query.get_mut(completed.entity) {
+            finished_events.write(ProcessFinishedWithOutput {
+                entity: completed.entity,
+                exit_status: completed.exit_status,
+                termination: completed.termination,
+                stdout: std::mem::take(&mut capture.stdout),
+                stderr: std::mem::take(&mut capture.stderr),
+                truncated: capture.truncated,
+            });
+            capture.reset();
+        }
+    }
+}
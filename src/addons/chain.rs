@@ -1,17 +1,48 @@
-use crate::local_command::LocalCommand;
-use crate::{Process, ProcessCompleted, ProcessError};
+use crate::local_command::{LocalCommand, LocalCommandState};
+use crate::{Process, ProcessCompleted, ProcessError, ProcessErrorInfo, ProcessTiming};
 use bevy::prelude::*;
+use bevy_log::tracing::{self, Span};
+use bevy_log::{info, info_span};
 use std::iter::IntoIterator;
 
 #[derive(Component)]
 pub struct Chain {
     pub(crate) commands: Vec<LocalCommand>,
+    /// The number of commands started so far.
+    step: usize,
+    /// The `chain` span, parent of the `command` span of each step.
+    pub(crate) span: Option<Span>,
 }
 
 impl Chain {
     pub fn new(commands: impl IntoIterator<Item = LocalCommand>) -> Self {
         Self {
             commands: commands.into_iter().collect(),
+            step: 0,
+            span: None,
+        }
+    }
+
+    /// Take the command of the next step out of the chain.
+    fn next_step(&mut self, entity: Entity) -> LocalCommand {
+        let span = self.span.get_or_insert_with(|| {
+            info_span!(
+                parent: None,
+                "chain",
+                entity = %entity,
+                steps = self.commands.len(),
+                success = tracing::field::Empty
+            )
+        });
+        self.step += 1;
+        info!(parent: &*span, step = self.step, "Starting chain step");
+        self.commands.remove(0)
+    }
+
+    /// Record the outcome of the chain in its span.
+    fn finish(&mut self, success: bool) {
+        if let Some(span) = self.span.take() {
+            span.record("success", success);
         }
     }
 }
@@ -26,6 +57,7 @@ pub fn chain_execution_system(
     mut commands: Commands,
     mut chain_query: Query<(Entity, &mut Chain)>,
     no_local_command: Query<(), Without<LocalCommand>>,
+    local_command_query: Query<&LocalCommand>,
     mut process_completed_events: EventReader<ProcessCompleted>,
     mut process_error_events: EventReader<ProcessError>,
     mut chain_completed_events: EventWriter<ChainCompletedEvent>,
@@ -36,16 +68,17 @@ pub fn chain_execution_system(
             if event.exit_status.success() {
                 // If there are more commands in the chain, start the next one
                 if !chain.commands.is_empty() {
-                    let local_command = chain.commands.remove(0);
+                    let local_command = chain.next_step(entity);
                     commands
                         .entity(entity)
                         .insert(local_command)
                         .remove::<Process>();
                 } else {
                     // If all commands are completed successfully, remove components
+                    chain.finish(true);
                     commands
                         .entity(entity)
-                        .remove::<(LocalCommand, Process, Chain)>();
+                        .remove::<(LocalCommand, Process, ProcessTiming, Chain)>();
                     chain_completed_events.write(ChainCompletedEvent {
                         entity,
                         success: true,
@@ -53,9 +86,10 @@ pub fn chain_execution_system(
                 }
             } else {
                 // If the process was not successful, abandon the rest of the chain
+                chain.finish(false);
                 commands
                     .entity(entity)
-                    .remove::<(LocalCommand, Process, Chain)>();
+                    .remove::<(LocalCommand, Process, ProcessTiming, Chain)>();
                 chain_completed_events.write(ChainCompletedEvent {
                     entity,
                     success: false,
@@ -65,11 +99,22 @@ pub fn chain_execution_system(
     }
     // Also consider ProcessError events as completed processes
     for event in process_error_events.read() {
-        if let Ok((entity, _)) = chain_query.get_mut(event.entity) {
+        // Only failing to start ends the process, other errors are reported along the way
+        if !matches!(event.info, ProcessErrorInfo::FailedToStart { .. }) {
+            continue;
+        }
+        // Unless the Retry addon is about to spawn the process again
+        if let Ok(local_command) = local_command_query.get(event.entity) {
+            if local_command.state == LocalCommandState::Ready {
+                continue;
+            }
+        }
+        if let Ok((entity, mut chain)) = chain_query.get_mut(event.entity) {
             // Abandon the rest of the chain
+            chain.finish(false);
             commands
                 .entity(entity)
-                .remove::<(LocalCommand, Process, Chain)>();
+                .remove::<(LocalCommand, Process, ProcessTiming, Chain)>();
             chain_completed_events.write(ChainCompletedEvent {
                 entity,
                 success: false,
@@ -80,7 +125,7 @@ pub fn chain_execution_system(
     // Start the first command for new Chain components without LocalCommand
     for (entity, mut chain) in chain_query.iter_mut() {
         if !chain.commands.is_empty() && no_local_command.get(entity).is_ok() {
-            let local_command = chain.commands.remove(0);
+            let local_command = chain.next_step(entity);
             commands.entity(entity).insert(local_command);
         }
     }
@@ -1,6 +1,12 @@
 use bevy::prelude::*;
 
-use crate::{process::Process, Chain, Delay, LocalCommand, LocalCommandState, Retry};
+use crate::{
+    process::{Process, ProcessTiming},
+    CaptureOutput, Chain, Delay, Interactive, LocalCommand, LocalCommandState, LogOutput,
+    LogToFile, OutputHistory, Retry, Timeout,
+};
+#[cfg(feature = "serde")]
+use crate::{JsonRpc, LanguageServer};
 
 #[derive(Debug, Component)]
 pub enum Cleanup {
@@ -32,8 +38,23 @@ pub(crate) fn cleanup_completed_process(
                 },
                 Cleanup::RemoveComponents => {
                     if let Ok(mut entity_commands) = commands.get_entity(entity) {
-                        entity_commands
-                            .remove::<(Process, Chain, Delay, Retry, Cleanup, LocalCommand)>();
+                        entity_commands.remove::<(
+                            Process,
+                            ProcessTiming,
+                            CaptureOutput,
+                            Chain,
+                            Delay,
+                            Retry,
+                            Timeout,
+                            Cleanup,
+                            OutputHistory,
+                            LogToFile,
+                            LogOutput,
+                            Interactive,
+                            LocalCommand,
+                        )>();
+                        #[cfg(feature = "serde")]
+                        entity_commands.remove::<(JsonRpc, LanguageServer)>();
                     }
                 },
             }
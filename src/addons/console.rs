@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_log::warn;
+
+use crate::{process::Process, AnsiParser, OutputStream, ProcessOutput};
+
+/// A scrolling console showing the output of a process, with an input line writing to its stdin.
+///
+/// Insert it on a UI node, pointing at the entity of a [`LocalCommand`](crate::LocalCommand): the
+/// node is filled with one text line per line of stdout and stderr (keeping their ANSI colors),
+/// followed by the input line. While the console is focused, typed text goes to the input line,
+/// which is written to the process with [`Process::println`] on Enter.
+///
+/// Consoles are not focused by default, so that typing goes to one console at a time. Keyboard
+/// input requires the [`InputPlugin`](bevy::input::InputPlugin) (part of the `DefaultPlugins`).
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{LocalCommand, ProcessConsole};
+///
+/// fn spawn_console(mut commands: Commands) {
+///     let shell = commands.spawn(LocalCommand::new("sh")).id();
+///     commands.spawn((
+///         Node {
+///             width: Val::Percent(100.),
+///             height: Val::Px(300.),
+///             ..default()
+///         },
+///         ProcessConsole::new(shell).scrollback(500).focused(true),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Component)]
+#[require(Node)]
+pub struct ProcessConsole {
+    pub(crate) target: Entity,
+    pub(crate) scrollback: usize,
+    pub(crate) auto_scroll: bool,
+    pub(crate) focused: bool,
+    pub(crate) font: TextFont,
+    pub(crate) stderr_color: Color,
+    input: String,
+    log: Option<Entity>,
+    input_line: Option<Entity>,
+    lines: VecDeque<Entity>,
+    stdout_parser: AnsiParser,
+    stderr_parser: AnsiParser,
+}
+
+impl ProcessConsole {
+    /// Creates a console showing the process of the `target` entity.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            scrollback: 1000,
+            auto_scroll: true,
+            focused: false,
+            font: TextFont::from_font_size(14.),
+            stderr_color: Color::srgb(1., 0.4, 0.4),
+            input: String::new(),
+            log: None,
+            input_line: None,
+            lines: VecDeque::new(),
+            stdout_parser: AnsiParser::default(),
+            stderr_parser: AnsiParser::default(),
+        }
+    }
+
+    /// Sets the maximum number of lines kept, older lines are removed (1000 by default).
+    pub fn scrollback(mut self, lines: usize) -> Self {
+        self.scrollback = lines;
+        self
+    }
+
+    /// Sets whether the console scrolls to the last line when a line is added (the default).
+    pub fn auto_scroll(mut self, auto_scroll: bool) -> Self {
+        self.auto_scroll = auto_scroll;
+        self
+    }
+
+    /// Sets the font of the lines.
+    pub fn font(mut self, font: TextFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Sets the color of stderr lines without an ANSI color.
+    pub fn stderr_color(mut self, color: Color) -> Self {
+        self.stderr_color = color;
+        self
+    }
+
+    /// Sets whether the console receives keyboard input (not by default).
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Sets whether the console receives keyboard input, e.g. when it is clicked.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Whether the console receives keyboard input.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The entity of the process shown by the console.
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// The text typed in the input line, not yet sent.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    fn input_text(&self) -> String {
+        format!("> {}", self.input)
+    }
+}
+
+/// Create the log and input line nodes of new consoles.
+pub(crate) fn setup_process_consoles(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ProcessConsole, &mut Node), Added<ProcessConsole>>,
+) {
+    for (entity, mut console, mut node) in query.iter_mut() {
+        node.flex_direction = FlexDirection::Column;
+        let log = commands
+            .spawn(Node {
+                flex_direction: FlexDirection::Column,
+                flex_grow: 1.,
+                overflow: Overflow::scroll_y(),
+                ..default()
+            })
+            .id();
+        let input_line = commands
+            .spawn((Text::new(console.input_text()), console.font.clone()))
+            .id();
+        commands.entity(entity).add_children(&[log, input_line]);
+        console.log = Some(log);
+        console.input_line = Some(input_line);
+    }
+}
+
+/// Add the output of processes to the consoles showing them.
+pub(crate) fn update_process_consoles(
+    mut commands: Commands,
+    mut consoles: Query<&mut ProcessConsole>,
+    mut scroll_positions: Query<&mut ScrollPosition>,
+    mut process_output_event: EventReader<ProcessOutput>,
+) {
+    for output in process_output_event.read() {
+        for mut console in consoles.iter_mut() {
+            let Some(log) = console.log.filter(|_| console.target == output.entity) else {
+                continue;
+            };
+            for line in output.lines() {
+                let (spans, default_color) = match output.stream {
+                    OutputStream::Stdout => (console.stdout_parser.parse(line), Color::WHITE),
+                    OutputStream::Stderr => {
+                        (console.stderr_parser.parse(line), console.stderr_color)
+                    },
+                };
+                let text_line = commands
+                    .spawn((Text::default(), console.font.clone()))
+                    .with_children(|parent| {
+                        for span in spans {
+                            let color = span.style.foreground.map_or(default_color, Color::from);
+                            parent.spawn((
+                                TextSpan::new(span.text),
+                                TextColor(color),
+                                console.font.clone(),
+                            ));
+                        }
+                    })
+                    .id();
+                commands.entity(log).add_child(text_line);
+                console.lines.push_back(text_line);
+            }
+            while console.lines.len() > console.scrollback {
+                if let Some(line) = console.lines.pop_front() {
+                    commands.entity(line).despawn();
+                }
+            }
+            if console.auto_scroll {
+                // The scroll position is clamped to the end of the content by the UI layout
+                if let Ok(mut scroll_position) = scroll_positions.get_mut(log) {
+                    scroll_position.offset_y = f32::MAX;
+                }
+            }
+        }
+    }
+}
+
+/// Type into the input line of focused consoles, writing it to the process stdin on Enter.
+pub(crate) fn handle_process_console_input(
+    mut consoles: Query<&mut ProcessConsole>,
+    mut texts: Query<&mut Text>,
+    mut processes: Query<&mut Process>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+) {
+    for event in keyboard_input_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        for mut console in consoles.iter_mut().filter(|console| console.focused) {
+            match &event.logical_key {
+                Key::Enter => {
+                    let input = std::mem::take(&mut console.input);
+                    match processes.get_mut(console.target) {
+                        Ok(mut process) => {
+                            if let Err(error) = process.println(&input) {
+                                warn!(
+                                    "Failed to write console input to {:?}: {error}",
+                                    console.target
+                                );
+                            }
+                        },
+                        Err(_) => {
+                            warn!("Console input ignored, {:?} is not running", console.target)
+                        },
+                    }
+                },
+                Key::Backspace => {
+                    console.input.pop();
+                },
+                _ => match &event.text {
+                    Some(text) if !text.chars().any(char::is_control) => {
+                        console.input.push_str(text)
+                    },
+                    _ => continue,
+                },
+            }
+            if let Some(mut text) = console
+                .input_line
+                .and_then(|entity| texts.get_mut(entity).ok())
+            {
+                text.0 = console.input_text();
+            }
+        }
+    }
+}
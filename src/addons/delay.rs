@@ -8,6 +8,15 @@ pub enum Delay {
     Fixed(Duration),
 }
 
+impl Delay {
+    /// How long the process start is delayed.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Delay::Fixed(duration) => *duration,
+        }
+    }
+}
+
 /// Apply delay settings to entities with LocalCommand + Delay components that have yet to be processed.
 ///
 /// State of LocalCommandState::Ready is required for the delay to be applied.
@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+
+use crate::OutputStream;
+
+/// A line of process output kept by [`OutputHistory`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryLine {
+    pub stream: OutputStream,
+    /// The line, without its line ending.
+    pub text: String,
+    /// When the line was received.
+    pub timestamp: SystemTime,
+}
+
+/// Keeps the last lines of text output of the process, for UI and post-mortem code.
+///
+/// Unlike [`ProcessOutput`](crate::ProcessOutput) events, the history lasts as long as the
+/// component (it is removed by [`Cleanup`](crate::Cleanup)), and is kept across retries. Once
+/// either the line or byte cap is reached, the oldest lines are dropped. Output read as bytes or
+/// frames is not recorded.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{LocalCommand, OutputHistory};
+///
+/// fn spawn_with_history(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("cargo").arg("build"),
+///         OutputHistory::new(500).max_bytes(64 * 1024),
+///     ));
+/// }
+///
+/// fn show_history(query: Query<&OutputHistory>) {
+///     for history in query.iter() {
+///         for line in history.lines() {
+///             println!("[{:?}] {}", line.stream, line.text);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Component)]
+pub struct OutputHistory {
+    max_lines: usize,
+    max_bytes: Option<usize>,
+    lines: VecDeque<HistoryLine>,
+    bytes: usize,
+    dropped: usize,
+}
+
+impl OutputHistory {
+    /// Creates a history keeping at most `max_lines` lines.
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            max_lines,
+            max_bytes: None,
+            lines: VecDeque::new(),
+            bytes: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Sets the maximum total length of the kept lines, in bytes (unlimited by default).
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// The kept lines, from oldest to newest.
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &HistoryLine> + ExactSizeIterator {
+        self.lines.iter()
+    }
+
+    /// The kept lines of a single stream, from oldest to newest.
+    pub fn stream(&self, stream: OutputStream) -> impl DoubleEndedIterator<Item = &HistoryLine> {
+        self.lines.iter().filter(move |line| line.stream == stream)
+    }
+
+    /// The number of kept lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The total length of the kept lines, in bytes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The number of lines dropped to respect the caps.
+    pub fn dropped_lines(&self) -> usize {
+        self.dropped
+    }
+
+    /// The kept lines joined with newlines.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.bytes + self.lines.len());
+        for line in &self.lines {
+            text.push_str(&line.text);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Remove all the kept lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.bytes = 0;
+    }
+
+    /// Append a line, dropping the oldest lines if a cap is exceeded.
+    pub(crate) fn push(&mut self, stream: OutputStream, text: &str, timestamp: SystemTime) {
+        self.lines.push_back(HistoryLine {
+            stream,
+            text: text.to_owned(),
+            timestamp,
+        });
+        self.bytes += text.len();
+        while self.lines.len() > self.max_lines
+            || self
+                .max_bytes
+                .is_some_and(|max_bytes| self.bytes > max_bytes)
+        {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            self.bytes -= line.text.len();
+            self.dropped += 1;
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_log::warn;
+
+use crate::{process::Process, OutputStream, ProcessOutput};
+
+/// The id of a prompt sent with [`Interactive::send`].
+pub type PromptId = u64;
+
+/// How long stdout must stay idle before its last line is matched, when it does not end with a
+/// newline yet.
+const PROMPT_IDLE: Duration = Duration::from_millis(50);
+
+/// The stdout line that ends the reply to a prompt of an [`Interactive`] process.
+///
+/// Output is matched line by line. Once the process stops writing, the output after the last
+/// newline is matched as well, so that the prompt of a REPL (like the `>>> ` of Python, or the
+/// `> ` of Lua) can be used as a terminator.
+#[derive(Debug, Clone)]
+pub enum Terminator {
+    /// A line equal to the string, ignoring trailing whitespace on both sides of the comparison.
+    Line(String),
+    /// A line starting with the string, like `bestmove` for UCI chess engines.
+    Prefix(String),
+    /// A line matching the regular expression.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Terminator {
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            Terminator::Line(marker) => line.trim_end() == marker.trim_end(),
+            Terminator::Prefix(prefix) => line.starts_with(prefix.as_str()),
+            #[cfg(feature = "regex")]
+            Terminator::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Talk with a REPL process one prompt at a time.
+///
+/// Prompts are queued with [`Interactive::send`], and written to stdin with
+/// [`Process::println`] once the reply to the previous prompt was received. The stdout lines
+/// following a prompt are collected until a line matches the [`Terminator`], and sent as a
+/// [`ReplyReceived`] event.
+///
+/// Output that is not part of a reply (like a startup banner) is ignored. When the process is
+/// restarted (e.g. by [`Retry`](crate::Retry)), the prompt waiting for a reply is dropped.
+///
+/// A prompt matched before the end of its line is not sent as [`ProcessOutput`].
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{Interactive, LocalCommand, Terminator};
+///
+/// fn spawn_engine(mut commands: Commands) {
+///     let mut interactive = Interactive::new(Terminator::Line("uciok".to_string()));
+///     interactive.send("uci");
+///     commands.spawn((LocalCommand::new("stockfish"), interactive));
+/// }
+///
+/// fn ask_for_move(mut query: Query<&mut Interactive>) {
+///     for mut interactive in query.iter_mut() {
+///         // "position" has no reply, send it together with "go"
+///         interactive.set_terminator(Terminator::Prefix("bestmove".to_string()));
+///         interactive.send("position startpos\ngo depth 10");
+///     }
+/// }
+///
+/// fn spawn_python(mut commands: Commands) {
+///     // Python writes its prompt to stderr, merge it into stdout
+///     let mut interactive = Interactive::new(Terminator::Line(">>>".to_string())).wait_for_prompt();
+///     interactive.send("6 * 7");
+///     commands.spawn((LocalCommand::new("sh").args(["-c", "python3 -i 2>&1"]), interactive));
+/// }
+/// ```
+#[derive(Debug, Component)]
+pub struct Interactive {
+    terminator: Terminator,
+    next_id: PromptId,
+    queue: VecDeque<(PromptId, String, Terminator)>,
+    waiting: Option<PendingReply>,
+    wait_for_prompt: bool,
+}
+
+#[derive(Debug)]
+struct PendingReply {
+    /// [`None`] for the prompt written when the process starts.
+    id: Option<PromptId>,
+    terminator: Terminator,
+    lines: Vec<String>,
+}
+
+impl Interactive {
+    pub fn new(terminator: Terminator) -> Self {
+        Self {
+            terminator,
+            next_id: 1,
+            queue: VecDeque::new(),
+            waiting: None,
+            wait_for_prompt: false,
+        }
+    }
+
+    /// Wait for the process to write the terminator before sending the first prompt, like REPLs
+    /// write their prompt when they start.
+    ///
+    /// The output written until then is ignored, without any [`ReplyReceived`] event.
+    pub fn wait_for_prompt(mut self) -> Self {
+        self.wait_for_prompt = true;
+        self
+    }
+
+    /// Sets the terminator of the prompts sent after this call.
+    pub fn set_terminator(&mut self, terminator: Terminator) {
+        self.terminator = terminator;
+    }
+
+    /// Queue a prompt, its reply is sent as a [`ReplyReceived`] event with the returned id.
+    pub fn send(&mut self, prompt: impl Into<String>) -> PromptId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queue
+            .push_back((id, prompt.into(), self.terminator.clone()));
+        id
+    }
+
+    /// The number of prompts waiting to be sent.
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Whether a prompt was sent and its reply is not complete yet.
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.is_some()
+    }
+}
+
+/// The reply of an [`Interactive`] process to a prompt.
+#[derive(Debug, Event)]
+pub struct ReplyReceived {
+    pub entity: Entity,
+    /// The id returned by [`Interactive::send`].
+    pub request_id: PromptId,
+    /// The lines written before the terminator line, each ending with a newline.
+    pub output: String,
+    /// The line that matched the [`Terminator`], without its newline.
+    ///
+    /// This may be a prompt that was not followed by a newline.
+    pub terminator: String,
+}
+
+/// Collect the stdout lines of [`Interactive`] processes into replies.
+///
+/// The last line of the output is matched as well once the process stops writing, as REPLs do not
+/// end their prompt with a newline.
+pub(crate) fn collect_interactive_replies(
+    mut query: Query<(Entity, &mut Interactive, Option<&Process>)>,
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut reply_events: EventWriter<ReplyReceived>,
+) {
+    for output in process_output_event.read() {
+        if output.stream != OutputStream::Stdout {
+            continue;
+        }
+        let Ok((_, mut interactive, _)) = query.get_mut(output.entity) else {
+            continue;
+        };
+        for line in output.lines() {
+            let Some(reply) = interactive.waiting.as_mut() else {
+                break;
+            };
+            if !reply.terminator.matches(line) {
+                reply.lines.push(line.to_owned());
+                continue;
+            }
+            if let Some(reply) = interactive.waiting.take() {
+                send_reply(output.entity, reply, line.to_owned(), &mut reply_events);
+            }
+        }
+    }
+
+    for (entity, mut interactive, process) in query.iter_mut() {
+        let (Some(reply), Some(stdout)) = (
+            &interactive.waiting,
+            process.and_then(|process| process.stdout.as_ref()),
+        ) else {
+            continue;
+        };
+        let Some(line) =
+            stdout.take_partial_line(PROMPT_IDLE, |line| reply.terminator.matches(line))
+        else {
+            continue;
+        };
+        if let Some(reply) = interactive.waiting.take() {
+            send_reply(entity, reply, line, &mut reply_events);
+        }
+    }
+}
+
+/// Send a complete reply, unless it is the one to the prompt written when the process starts.
+fn send_reply(
+    entity: Entity,
+    reply: PendingReply,
+    terminator: String,
+    reply_events: &mut EventWriter<ReplyReceived>,
+) {
+    let Some(request_id) = reply.id else {
+        return;
+    };
+    let mut output = String::new();
+    for line in reply.lines {
+        output.push_str(&line);
+        output.push('\n');
+    }
+    reply_events.write(ReplyReceived {
+        entity,
+        request_id,
+        output,
+        terminator,
+    });
+}
+
+/// Write the next queued prompt of [`Interactive`] processes that are not waiting for a reply.
+pub(crate) fn send_interactive_prompts(mut query: Query<(Entity, &mut Interactive, &mut Process)>) {
+    for (entity, mut interactive, mut process) in query.iter_mut() {
+        // The reply will never come from a restarted process
+        if process.is_added() {
+            interactive.waiting = interactive.wait_for_prompt.then(|| PendingReply {
+                id: None,
+                terminator: interactive.terminator.clone(),
+                lines: Vec::new(),
+            });
+        }
+        if interactive.waiting.is_some() {
+            continue;
+        }
+        let Some((id, prompt, terminator)) = interactive.queue.pop_front() else {
+            continue;
+        };
+        match process.println(&prompt) {
+            Ok(()) => {
+                interactive.waiting = Some(PendingReply {
+                    id: Some(id),
+                    terminator,
+                    lines: Vec::new(),
+                });
+            },
+            Err(error) => warn!("Failed to send prompt {id} to {entity:?}: {error}"),
+        }
+    }
+}
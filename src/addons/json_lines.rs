@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+
+use crate::{OutputStream, ProcessOutput};
+
+/// Parses each stdout line of the process as JSON into `T`, sent as [`ProcessMessage<T>`] events.
+///
+/// Requires [`JsonLinesPlugin<T>`] to be added to the app. Empty lines are ignored, lines that
+/// fail to parse are sent as [`JsonLinesParseError`] events. The [`ProcessOutput`] events are
+/// still sent.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{JsonLinesOutput, LocalCommand};
+///
+/// fn run_cargo(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("cargo").args(["build", "--message-format=json"]),
+///         JsonLinesOutput::<serde_json::Value>::new(),
+///     ));
+/// }
+/// ```
+#[derive(Component)]
+pub struct JsonLinesOutput<T: Send + Sync + 'static> {
+    message: PhantomData<fn() -> T>,
+}
+
+impl<T: Send + Sync + 'static> JsonLinesOutput<T> {
+    pub fn new() -> Self {
+        Self {
+            message: PhantomData,
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for JsonLinesOutput<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + Sync + 'static> std::fmt::Debug for JsonLinesOutput<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonLinesOutput")
+            .field("message", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+/// A line of process output parsed by [`JsonLinesOutput<T>`].
+#[derive(Debug, Event)]
+pub struct ProcessMessage<T: Send + Sync + 'static> {
+    pub entity: Entity,
+    pub message: T,
+}
+
+/// A line of process output that [`JsonLinesOutput`] failed to parse.
+#[derive(Debug, Event)]
+pub struct JsonLinesParseError {
+    pub entity: Entity,
+    /// The line that failed to parse.
+    pub line: String,
+    /// The parse error message, including its line and column.
+    pub error: String,
+}
+
+/// Adds parsing of [`JsonLinesOutput<T>`] processes.
+///
+/// Add one plugin for each message type, after
+/// [`BevyLocalCommandsPlugin`](crate::BevyLocalCommandsPlugin).
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{BevyLocalCommandsPlugin, JsonLinesPlugin};
+///
+/// App::new().add_plugins((
+///     BevyLocalCommandsPlugin,
+///     JsonLinesPlugin::<serde_json::Value>::default(),
+/// ));
+/// ```
+pub struct JsonLinesPlugin<T> {
+    message: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for JsonLinesPlugin<T> {
+    fn default() -> Self {
+        Self {
+            message: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + Sync + 'static> Plugin for JsonLinesPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ProcessMessage<T>>()
+            .add_event::<JsonLinesParseError>()
+            .add_systems(
+                Update,
+                parse_json_lines::<T>
+                    .after(crate::systems::handle_process_output)
+                    .before(crate::addons::cleanup::cleanup_completed_process),
+            );
+    }
+}
+
+/// Parse the stdout lines of [`JsonLinesOutput<T>`] processes.
+///
+/// Runs before cleanup so that the last lines of despawned processes are still parsed.
+fn parse_json_lines<T: DeserializeOwned + Send + Sync + 'static>(
+    query: Query<(), With<JsonLinesOutput<T>>>,
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_message_event: EventWriter<ProcessMessage<T>>,
+    mut parse_error_event: EventWriter<JsonLinesParseError>,
+) {
+    for output in process_output_event.read() {
+        if output.stream != OutputStream::Stdout || !query.contains(output.entity) {
+            continue;
+        }
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(message) => {
+                    process_message_event.write(ProcessMessage {
+                        entity: output.entity,
+                        message,
+                    });
+                },
+                Err(error) => {
+                    parse_error_event.write(JsonLinesParseError {
+                        entity: output.entity,
+                        line: line.to_owned(),
+                        error: error.to_string(),
+                    });
+                },
+            }
+        }
+    }
+}
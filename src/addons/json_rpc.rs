@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use bevy::ecs::component::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+use bevy_log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    process::Process, ContentLengthDecoder, LinesDecoder, LocalCommand, OutputStream,
+    ProcessOutputFrame,
+};
+
+/// The id of a request sent with [`JsonRpc::request`].
+pub type JsonRpcId = u64;
+
+/// How JSON-RPC messages are delimited on stdin and stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonRpcFraming {
+    /// One message per line.
+    #[default]
+    Lines,
+    /// Each message is preceded by a `Content-Length` header, as used by language servers.
+    ContentLength,
+}
+
+/// The error object of a failed JSON-RPC request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+    method: String,
+    timer: Option<Timer>,
+}
+
+/// Talk JSON-RPC 2.0 with the process over its stdin and stdout.
+///
+/// Insert it on the entity of a [`LocalCommand`] (before or after it): the stdout of the process
+/// is then decoded according to the [`JsonRpcFraming`], and each message is sent as a
+/// [`JsonRpcResponse`], [`JsonRpcNotification`] or [`JsonRpcServerRequest`] event.
+///
+/// Requests that are not answered within the request timeout (30s by default) are sent as
+/// [`JsonRpcTimeoutEvent`]s, and a late response to them is ignored. When the process is
+/// restarted (e.g. by [`Retry`](crate::Retry)), the pending requests are dropped without any
+/// event, as they were never received by the new process.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{JsonRpc, JsonRpcFraming, LocalCommand, Process};
+/// use serde_json::json;
+///
+/// fn spawn_helper(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("asset-compiler").arg("--stdio"),
+///         JsonRpc::new(JsonRpcFraming::Lines),
+///     ));
+/// }
+///
+/// fn compile(mut query: Query<(&mut JsonRpc, &mut Process)>) {
+///     for (mut rpc, mut process) in query.iter_mut() {
+///         if let Ok(id) = rpc.request(&mut process, "compile", json!({ "path": "level.gltf" })) {
+///             println!("Sent compile request {id}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Component)]
+#[component(on_insert = on_insert_json_rpc)]
+pub struct JsonRpc {
+    framing: JsonRpcFraming,
+    request_timeout: Option<Duration>,
+    next_id: JsonRpcId,
+    pending: HashMap<JsonRpcId, PendingRequest>,
+}
+
+/// Decode the stdout of the command according to the framing.
+fn on_insert_json_rpc(mut world: DeferredWorld, context: HookContext) {
+    let Some(framing) = world
+        .get::<JsonRpc>(context.entity)
+        .map(|json_rpc| json_rpc.framing)
+    else {
+        return;
+    };
+    if let Some(mut local_command) = world.get_mut::<LocalCommand>(context.entity) {
+        set_framing(&mut local_command, framing);
+    }
+}
+
+/// Decode the stdout of a command inserted after its [`JsonRpc`], like the next step of a
+/// [`Chain`](crate::Chain).
+pub(crate) fn on_insert_local_command(
+    trigger: Trigger<OnInsert, LocalCommand>,
+    mut query: Query<(&JsonRpc, &mut LocalCommand)>,
+) {
+    if let Ok((json_rpc, mut local_command)) = query.get_mut(trigger.target()) {
+        set_framing(&mut local_command, json_rpc.framing);
+    }
+}
+
+fn set_framing(local_command: &mut LocalCommand, framing: JsonRpcFraming) {
+    match framing {
+        JsonRpcFraming::Lines => local_command.set_decoder(LinesDecoder),
+        JsonRpcFraming::ContentLength => local_command.set_decoder(ContentLengthDecoder),
+    }
+}
+
+impl JsonRpc {
+    pub fn new(framing: JsonRpcFraming) -> Self {
+        Self {
+            framing,
+            request_timeout: Some(Duration::from_secs(30)),
+            next_id: 1,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Sets how long to wait for the response to a request, `None` to wait forever.
+    pub fn request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    pub fn framing(&self) -> JsonRpcFraming {
+        self.framing
+    }
+
+    /// The number of requests still waiting for a response.
+    pub fn pending_requests(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether the request is still waiting for a response.
+    pub fn is_pending(&self, id: JsonRpcId) -> bool {
+        self.pending.contains_key(&id)
+    }
+
+    /// Send a request to the process, its response is sent as a [`JsonRpcResponse`] event.
+    ///
+    /// `params` is omitted from the request if it is `Value::Null`.
+    pub fn request(
+        &mut self,
+        process: &mut Process,
+        method: &str,
+        params: Value,
+    ) -> io::Result<JsonRpcId> {
+        let id = self.next_id;
+        let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        self.send(process, &message)?;
+
+        self.next_id += 1;
+        self.pending.insert(
+            id,
+            PendingRequest {
+                method: method.to_owned(),
+                timer: self
+                    .request_timeout
+                    .map(|timeout| Timer::new(timeout, TimerMode::Once)),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Send a notification to the process, which does not get a response.
+    ///
+    /// `params` is omitted from the notification if it is `Value::Null`.
+    pub fn notify(&mut self, process: &mut Process, method: &str, params: Value) -> io::Result<()> {
+        let mut message = json!({ "jsonrpc": "2.0", "method": method });
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        self.send(process, &message)
+    }
+
+    /// Respond to a [`JsonRpcServerRequest`] of the process.
+    pub fn respond(
+        &mut self,
+        process: &mut Process,
+        id: Value,
+        result: Result<Value, JsonRpcError>,
+    ) -> io::Result<()> {
+        let message = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        self.send(process, &message)
+    }
+
+    /// Write a framed message to the stdin of the process.
+    fn send(&self, process: &mut Process, message: &Value) -> io::Result<()> {
+        let body = serde_json::to_vec(message)?;
+        match self.framing {
+            JsonRpcFraming::Lines => {
+                process.write_all(&body)?;
+                process.write_all(b"\n")?;
+            },
+            JsonRpcFraming::ContentLength => {
+                write!(process, "Content-Length: {}\r\n\r\n", body.len())?;
+                process.write_all(&body)?;
+            },
+        }
+        process.flush()
+    }
+}
+
+/// The response of the process to a [`JsonRpc::request`].
+#[derive(Debug, Event)]
+pub struct JsonRpcResponse {
+    pub entity: Entity,
+    pub id: JsonRpcId,
+    /// The method of the request.
+    pub method: String,
+    pub result: Result<Value, JsonRpcError>,
+}
+
+/// A notification sent by the process.
+#[derive(Debug, Event)]
+pub struct JsonRpcNotification {
+    pub entity: Entity,
+    pub method: String,
+    pub params: Value,
+}
+
+/// A request sent by the process, answer it with [`JsonRpc::respond`].
+#[derive(Debug, Event)]
+pub struct JsonRpcServerRequest {
+    pub entity: Entity,
+    pub id: Value,
+    pub method: String,
+    pub params: Value,
+}
+
+/// A request that did not get a response within the request timeout of its [`JsonRpc`].
+#[derive(Debug, Event)]
+pub struct JsonRpcTimeoutEvent {
+    pub entity: Entity,
+    pub id: JsonRpcId,
+    pub method: String,
+}
+
+/// A message read from the process, which can be any of the JSON-RPC message kinds.
+#[derive(Deserialize)]
+struct IncomingMessage {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcError>,
+}
+
+/// Drop the pending requests of restarted [`JsonRpc`] processes, they will never get a response.
+pub(crate) fn reset_json_rpc_requests(mut query: Query<&mut JsonRpc, Added<Process>>) {
+    for mut json_rpc in query.iter_mut() {
+        json_rpc.pending.clear();
+    }
+}
+
+/// Parse the stdout frames of [`JsonRpc`] processes into responses, notifications and requests.
+///
+/// Messages that are not valid JSON-RPC, or responses to unknown requests, are logged and ignored.
+pub(crate) fn handle_json_rpc_output(
+    mut query: Query<&mut JsonRpc>,
+    mut process_output_frame_event: EventReader<ProcessOutputFrame>,
+    mut response_events: EventWriter<JsonRpcResponse>,
+    mut notification_events: EventWriter<JsonRpcNotification>,
+    mut server_request_events: EventWriter<JsonRpcServerRequest>,
+) {
+    for output in process_output_frame_event.read() {
+        if output.stream != OutputStream::Stdout {
+            continue;
+        }
+        let Ok(mut json_rpc) = query.get_mut(output.entity) else {
+            continue;
+        };
+        // Skip empty lines between messages
+        if output.frame().iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let message = match serde_json::from_slice::<IncomingMessage>(output.frame()) {
+            Ok(message) => message,
+            Err(error) => {
+                warn!("Invalid JSON-RPC message from {:?}: {error}", output.entity);
+                continue;
+            },
+        };
+
+        match (message.method, message.id) {
+            (Some(method), Some(id)) => {
+                server_request_events.write(JsonRpcServerRequest {
+                    entity: output.entity,
+                    id,
+                    method,
+                    params: message.params,
+                });
+            },
+            (Some(method), None) => {
+                notification_events.write(JsonRpcNotification {
+                    entity: output.entity,
+                    method,
+                    params: message.params,
+                });
+            },
+            (None, id) => {
+                let Some((id, request)) = id
+                    .as_ref()
+                    .and_then(Value::as_u64)
+                    .and_then(|id| json_rpc.pending.remove(&id).map(|request| (id, request)))
+                else {
+                    warn!(
+                        "JSON-RPC response from {:?} to unknown request {id:?}",
+                        output.entity
+                    );
+                    continue;
+                };
+                let result = match message.error {
+                    Some(error) => Err(error),
+                    None => Ok(message.result.unwrap_or(Value::Null)),
+                };
+                response_events.write(JsonRpcResponse {
+                    entity: output.entity,
+                    id,
+                    method: request.method,
+                    result,
+                });
+            },
+        }
+    }
+}
+
+/// Give up on the pending requests that exceeded the request timeout of their [`JsonRpc`].
+///
+/// The timeout is measured in real time.
+pub(crate) fn apply_json_rpc_timeouts(
+    mut query: Query<(Entity, &mut JsonRpc)>,
+    mut timeout_events: EventWriter<JsonRpcTimeoutEvent>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut json_rpc) in query.iter_mut() {
+        let mut timed_out = Vec::new();
+        for (id, request) in json_rpc.pending.iter_mut() {
+            if let Some(timer) = request.timer.as_mut() {
+                if timer.tick(time.delta()).finished() {
+                    timed_out.push(*id);
+                }
+            }
+        }
+        for id in timed_out {
+            if let Some(request) = json_rpc.pending.remove(&id) {
+                warn!(
+                    "JSON-RPC request {id} ({}) of {entity:?} timed out",
+                    request.method
+                );
+                timeout_events.write(JsonRpcTimeoutEvent {
+                    entity,
+                    id,
+                    method: request.method,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_log::tracing::{self, Span};
+use bevy_log::{info_span, Level};
+
+use crate::{process::Process, LocalCommand, OutputStream, ProcessOutput};
+
+/// Logs each line of text output of the process with [`bevy_log`], so that it shows up in the
+/// same logs as the app.
+///
+/// Lines are logged with the `bevy_local_commands::output` target, inside a `process` span
+/// carrying the `command` name (the program file name without extension by default), `entity`
+/// and `pid`. By default stdout is logged at the `DEBUG` level and stderr at the `WARN` level,
+/// `None` disables the logging of a stream. Output read as bytes or frames is not logged.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{LocalCommand, LogOutput};
+/// use bevy_log::Level;
+///
+/// fn build_assets(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("asset-compiler"),
+///         LogOutput::new()
+///             .name("assets")
+///             .stdout_level(Some(Level::INFO))
+///             .stderr_level(Some(Level::ERROR)),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Component)]
+pub struct LogOutput {
+    name: Option<String>,
+    stdout_level: Option<Level>,
+    stderr_level: Option<Level>,
+    span: Option<Span>,
+}
+
+impl LogOutput {
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            stdout_level: Some(Level::DEBUG),
+            stderr_level: Some(Level::WARN),
+            span: None,
+        }
+    }
+
+    /// Sets the `command` name of the span, instead of the program file name without extension.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the level stdout lines are logged at, `None` to not log them.
+    pub fn stdout_level(mut self, level: Option<Level>) -> Self {
+        self.stdout_level = level;
+        self
+    }
+
+    /// Sets the level stderr lines are logged at, `None` to not log them.
+    pub fn stderr_level(mut self, level: Option<Level>) -> Self {
+        self.stderr_level = level;
+        self
+    }
+
+    fn level(&self, stream: OutputStream) -> Option<Level> {
+        match stream {
+            OutputStream::Stdout => self.stdout_level,
+            OutputStream::Stderr => self.stderr_level,
+        }
+    }
+}
+
+impl Default for LogOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Log a line at a level only known at runtime.
+fn log_line(level: Level, stream: OutputStream, line: &str) {
+    macro_rules! log_line {
+        ($level:expr) => {
+            tracing::event!(target: "bevy_local_commands::output", $level, ?stream, "{line}")
+        };
+    }
+
+    match level {
+        Level::ERROR => log_line!(Level::ERROR),
+        Level::WARN => log_line!(Level::WARN),
+        Level::INFO => log_line!(Level::INFO),
+        Level::DEBUG => log_line!(Level::DEBUG),
+        Level::TRACE => log_line!(Level::TRACE),
+    }
+}
+
+/// Log the text output of [`LogOutput`] processes.
+///
+/// A new span is created for each started process, including retried ones.
+pub(crate) fn log_process_output(
+    mut query: Query<(Entity, &mut LogOutput, &LocalCommand, Option<Ref<Process>>)>,
+    mut process_output_event: EventReader<ProcessOutput>,
+) {
+    for (entity, mut log, local_command, process) in query.iter_mut() {
+        let Some(process) = process.filter(|process| process.is_added()) else {
+            continue;
+        };
+        let name = log
+            .name
+            .clone()
+            .unwrap_or_else(|| local_command.program_name());
+        log.span = Some(info_span!(
+            "process",
+            command = %name,
+            entity = %entity,
+            pid = process.id()
+        ));
+    }
+
+    for output in process_output_event.read() {
+        let Ok((_, log, ..)) = query.get(output.entity) else {
+            continue;
+        };
+        let Some(level) = log.level(output.stream) else {
+            continue;
+        };
+        let _entered = log.span.as_ref().map(Span::enter);
+        for line in output.lines() {
+            log_line(level, output.stream, line);
+        }
+    }
+}
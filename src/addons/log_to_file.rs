@@ -0,0 +1,353 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy_log::warn;
+
+use crate::{
+    process::Process, LocalCommand, OutputStream, ProcessCompleted, ProcessOutput,
+    ProcessOutputBytes,
+};
+
+/// Writes the output of each process to a log file.
+///
+/// The path is a template, in which these placeholders are replaced when the process starts:
+///
+/// - `{entity}`: the entity of the command, e.g. `12v1`
+/// - `{program}`: the file name of the program, without extension
+/// - `{pid}`: the id of the process
+/// - `{start}`: the UTC start time, e.g. `20261017T093512Z`
+/// - `{stream}`: `stdout` or `stderr`, to write the streams to separate files
+///
+/// Without `{stream}`, both streams go to the same file. Files are appended to, and rotated once
+/// they exceed the size limit: `app.log` is renamed to `app.log.1`, `app.log.1` to `app.log.2`
+/// and so on, up to the number of kept files. Writing the logs does not consume the
+/// [`ProcessOutput`] events, and failures to write them are logged as warnings.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{LocalCommand, LogToFile};
+///
+/// fn build_with_logs(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("cargo").arg("build"),
+///         LogToFile::new("logs/{program}-{start}-{pid}.log")
+///             .rotate(10 * 1024 * 1024, 3)
+///             .timestamps(true),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Component)]
+pub struct LogToFile {
+    template: String,
+    max_bytes: Option<u64>,
+    max_files: usize,
+    timestamps: bool,
+    files: Vec<LogFile>,
+    paths: Vec<PathBuf>,
+}
+
+/// An open log file and the streams written to it.
+#[derive(Debug)]
+struct LogFile {
+    path: PathBuf,
+    streams: Vec<OutputStream>,
+    writer: BufWriter<File>,
+    size: u64,
+}
+
+impl LogToFile {
+    /// Creates a log without size limit, using the path template.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            max_bytes: None,
+            max_files: 0,
+            timestamps: false,
+            files: Vec::new(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Rotates the log once it exceeds `max_bytes`, keeping at most `max_files` previous files.
+    pub fn rotate(mut self, max_bytes: u64, max_files: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self.max_files = max_files;
+        self
+    }
+
+    /// Sets whether each line starts with the UTC time it was received at (disabled by default).
+    pub fn timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// The paths of the files of the last started process, which are kept once it completes.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.paths.iter().map(PathBuf::as_path)
+    }
+
+    /// Open the files of a newly started process.
+    fn open(&mut self, entity: Entity, program: &str, pid: u32) -> io::Result<()> {
+        self.files.clear();
+        self.paths.clear();
+        let start = format_time(SystemTime::now(), true);
+        for stream in [OutputStream::Stdout, OutputStream::Stderr] {
+            let stream_name = match stream {
+                OutputStream::Stdout => "stdout",
+                OutputStream::Stderr => "stderr",
+            };
+            let path = PathBuf::from(
+                self.template
+                    .replace("{entity}", &entity.to_string())
+                    .replace("{program}", program)
+                    .replace("{pid}", &pid.to_string())
+                    .replace("{start}", &start)
+                    .replace("{stream}", stream_name),
+            );
+            if let Some(file) = self.files.iter_mut().find(|file| file.path == path) {
+                file.streams.push(stream);
+                continue;
+            }
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            self.paths.push(path.clone());
+            self.files.push(LogFile {
+                size: file.metadata()?.len(),
+                path,
+                streams: vec![stream],
+                writer: BufWriter::new(file),
+            });
+        }
+        Ok(())
+    }
+
+    /// Write output to the file of the stream, rotating it first if it is full.
+    fn write(&mut self, stream: OutputStream, text: &[u8]) -> io::Result<()> {
+        let (max_bytes, max_files, timestamps) = (self.max_bytes, self.max_files, self.timestamps);
+        let Some(file) = self
+            .files
+            .iter_mut()
+            .find(|file| file.streams.contains(&stream))
+        else {
+            return Ok(());
+        };
+        if max_bytes.is_some_and(|max_bytes| file.size >= max_bytes) {
+            file.rotate(max_files)?;
+        }
+
+        if timestamps {
+            let prefix = format!("{} ", format_time(SystemTime::now(), false));
+            for line in text.split_inclusive(|byte| *byte == b'\n') {
+                file.writer.write_all(prefix.as_bytes())?;
+                file.writer.write_all(line)?;
+                file.size += (prefix.len() + line.len()) as u64;
+            }
+        } else {
+            file.writer.write_all(text)?;
+            file.size += text.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files
+            .iter_mut()
+            .try_for_each(|file| file.writer.flush())
+    }
+}
+
+impl LogFile {
+    /// Shift the previous files (dropping the oldest), and start a new file.
+    fn rotate(&mut self, max_files: usize) -> io::Result<()> {
+        self.writer.flush()?;
+        let numbered = |index: usize| {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{index}"));
+            PathBuf::from(path)
+        };
+        if max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..max_files).rev() {
+                let from = numbered(index);
+                if from.exists() {
+                    fs::rename(from, numbered(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, numbered(1))?;
+        }
+        self.writer = BufWriter::new(File::create(&self.path)?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Format the time in UTC, as `2026-10-17T09:35:12.345Z`, or `20261017T093512Z` for file names.
+fn format_time(time: SystemTime, compact: bool) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (days, seconds_of_day) = ((seconds / 86400) as i64, seconds % 86400);
+    let (hour, minute, second) = (
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+    );
+
+    // Convert days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    if compact {
+        format!("{year:04}{month:02}{day:02}T{hour:02}{minute:02}{second:02}Z")
+    } else {
+        let millis = since_epoch.subsec_millis();
+        format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{millis:03}Z")
+    }
+}
+
+/// Open the log files of newly started processes, and write their output to them.
+///
+/// The files are closed once the process completes.
+pub(crate) fn write_output_to_files(
+    mut query: Query<(Entity, &mut LogToFile, &LocalCommand, Option<Ref<Process>>)>,
+    mut process_output_event: EventReader<ProcessOutput>,
+    mut process_output_bytes_event: EventReader<ProcessOutputBytes>,
+    mut process_completed_event: EventReader<ProcessCompleted>,
+) {
+    for (entity, mut log, local_command, process) in query.iter_mut() {
+        let Some(process) = process.filter(|process| process.is_added()) else {
+            continue;
+        };
+        if let Err(error) = log.open(entity, &local_command.program_name(), process.id()) {
+            warn!("Failed to open the log file of {entity:?}: {error}");
+            log.files.clear();
+        }
+    }
+
+    let outputs = process_output_event
+        .read()
+        .map(|output| (output.entity, output.stream, output.output.as_bytes()));
+    let bytes_outputs = process_output_bytes_event
+        .read()
+        .map(|output| (output.entity, output.stream, output.bytes()));
+    for (entity, stream, bytes) in outputs.chain(bytes_outputs) {
+        if let Ok((_, mut log, ..)) = query.get_mut(entity) {
+            if let Err(error) = log.write(stream, bytes) {
+                warn!("Failed to write the log file of {entity:?}: {error}");
+            }
+        }
+    }
+
+    for (entity, mut log, ..) in query.iter_mut() {
+        if let Err(error) = log.flush() {
+            warn!("Failed to write the log file of {entity:?}: {error}");
+        }
+    }
+    for completed in process_completed_event.read() {
+        if let Ok((_, mut log, ..)) = query.get_mut(completed.entity) {
+            log.files.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn at(seconds: u64, millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds) + Duration::from_millis(millis)
+    }
+
+    /// A log file in a new temporary directory, removed by the caller.
+    fn log_file(name: &str) -> LogFile {
+        let dir =
+            std::env::temp_dir().join(format!("bevy_local_commands_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("program.log");
+        LogFile {
+            writer: BufWriter::new(File::create(&path).unwrap()),
+            path,
+            streams: vec![OutputStream::Stdout],
+            size: 0,
+        }
+    }
+
+    fn contents(path: &Path, suffix: &str) -> Option<String> {
+        let mut path = path.as_os_str().to_owned();
+        path.push(suffix);
+        fs::read_to_string(path).ok()
+    }
+
+    #[test]
+    fn format_time_at_epoch() {
+        assert_eq!(format_time(UNIX_EPOCH, true), "19700101T000000Z");
+        assert_eq!(format_time(UNIX_EPOCH, false), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn format_time_on_leap_day() {
+        let time = at(1709210096, 789);
+        assert_eq!(format_time(time, true), "20240229T123456Z");
+        assert_eq!(format_time(time, false), "2024-02-29T12:34:56.789Z");
+        assert_eq!(format_time(at(1709251200, 0), true), "20240301T000000Z");
+        // Not a leap year
+        assert_eq!(format_time(at(4107542400, 0), true), "21000301T000000Z");
+    }
+
+    #[test]
+    fn rotate_shifts_numbered_files() {
+        let mut file = log_file("rotate");
+        for text in ["first", "second", "third"] {
+            file.writer.write_all(text.as_bytes()).unwrap();
+            file.size = text.len() as u64;
+            file.rotate(2).unwrap();
+        }
+        file.writer.write_all(b"fourth").unwrap();
+        file.writer.flush().unwrap();
+
+        assert_eq!(file.size, 0);
+        assert_eq!(contents(&file.path, "").as_deref(), Some("fourth"));
+        assert_eq!(contents(&file.path, ".1").as_deref(), Some("third"));
+        assert_eq!(contents(&file.path, ".2").as_deref(), Some("second"));
+        assert_eq!(contents(&file.path, ".3"), None);
+        fs::remove_dir_all(file.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rotate_without_numbered_files_truncates() {
+        let mut file = log_file("rotate_truncate");
+        file.writer.write_all(b"first").unwrap();
+        file.rotate(0).unwrap();
+        file.writer.write_all(b"second").unwrap();
+        file.writer.flush().unwrap();
+
+        assert_eq!(contents(&file.path, "").as_deref(), Some("second"));
+        assert_eq!(contents(&file.path, ".1"), None);
+        fs::remove_dir_all(file.path.parent().unwrap()).unwrap();
+    }
+}
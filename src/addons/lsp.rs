@@ -0,0 +1,237 @@
+use std::io;
+
+use bevy::prelude::*;
+use bevy_log::warn;
+use serde_json::{json, Value};
+
+use crate::{
+    process::Process, JsonRpc, JsonRpcError, JsonRpcFraming, JsonRpcId, JsonRpcResponse,
+    JsonRpcTimeoutEvent,
+};
+
+/// The progress of the Language Server Protocol handshake with a [`LanguageServer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageServerState {
+    /// The process has not been initialized yet.
+    Starting,
+    /// The `initialize` request was sent.
+    Initializing(JsonRpcId),
+    /// The server is initialized and accepts requests.
+    Ready,
+    /// The `shutdown` request was sent.
+    ShuttingDown(JsonRpcId),
+    /// The `exit` notification was sent.
+    Exited,
+    /// The server failed to initialize.
+    Failed,
+}
+
+/// Drive the process as a Language Server Protocol server.
+///
+/// Each spawned process is sent the `initialize` request, followed by the `initialized`
+/// notification once it responds, after which a [`LanguageServerReady`] event is sent. Messages
+/// are framed with `Content-Length` headers by the required [`JsonRpc`] component, which sends
+/// and receives everything else: use [`JsonRpc::request`] and [`JsonRpc::notify`] once the server
+/// is ready, and read the [`JsonRpcResponse`], [`JsonRpcNotification`] and
+/// [`JsonRpcServerRequest`] events.
+///
+/// [`JsonRpcNotification`]: crate::JsonRpcNotification
+/// [`JsonRpcServerRequest`]: crate::JsonRpcServerRequest
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::{LanguageServer, LocalCommand};
+/// use serde_json::json;
+///
+/// fn spawn_language_server(mut commands: Commands) {
+///     commands.spawn((
+///         LocalCommand::new("rust-analyzer"),
+///         LanguageServer::new("file:///home/user/project")
+///             .capabilities(json!({ "textDocument": { "hover": {} } })),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Component)]
+#[require(JsonRpc = JsonRpc::new(JsonRpcFraming::ContentLength))]
+pub struct LanguageServer {
+    root_uri: Option<String>,
+    capabilities: Value,
+    initialization_options: Value,
+    state: LanguageServerState,
+}
+
+impl LanguageServer {
+    /// Creates a language server for the workspace at `root_uri` (e.g. `file:///path/to/project`).
+    pub fn new(root_uri: impl Into<String>) -> Self {
+        Self {
+            root_uri: Some(root_uri.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the client capabilities sent with the `initialize` request (none by default).
+    pub fn capabilities(mut self, capabilities: Value) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the `initializationOptions` sent with the `initialize` request.
+    pub fn initialization_options(mut self, options: Value) -> Self {
+        self.initialization_options = options;
+        self
+    }
+
+    pub fn state(&self) -> LanguageServerState {
+        self.state
+    }
+
+    /// Whether the server is initialized and accepts requests.
+    pub fn is_ready(&self) -> bool {
+        self.state == LanguageServerState::Ready
+    }
+
+    /// Ask the server to shut down, it is sent the `exit` notification once it responds.
+    ///
+    /// Does nothing if the server is not ready.
+    pub fn shutdown(&mut self, json_rpc: &mut JsonRpc, process: &mut Process) -> io::Result<()> {
+        if self.state != LanguageServerState::Ready {
+            return Ok(());
+        }
+        let id = json_rpc.request(process, "shutdown", Value::Null)?;
+        self.state = LanguageServerState::ShuttingDown(id);
+        Ok(())
+    }
+
+    fn initialize_params(&self) -> Value {
+        let mut params = json!({
+            "processId": std::process::id(),
+            "clientInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "rootUri": self.root_uri,
+            "capabilities": self.capabilities,
+        });
+        if let Some(root_uri) = &self.root_uri {
+            params["workspaceFolders"] = json!([{ "uri": root_uri, "name": root_uri }]);
+        }
+        if !self.initialization_options.is_null() {
+            params["initializationOptions"] = self.initialization_options.clone();
+        }
+        params
+    }
+}
+
+impl Default for LanguageServer {
+    /// A language server without a workspace.
+    fn default() -> Self {
+        Self {
+            root_uri: None,
+            capabilities: json!({}),
+            initialization_options: Value::Null,
+            state: LanguageServerState::Starting,
+        }
+    }
+}
+
+/// A [`LanguageServer`] was initialized and accepts requests.
+#[derive(Debug, Event)]
+pub struct LanguageServerReady {
+    pub entity: Entity,
+    /// The `capabilities` of the `initialize` result.
+    pub capabilities: Value,
+    /// The `serverInfo` of the `initialize` result, `Value::Null` if the server did not send it.
+    pub server_info: Value,
+}
+
+/// A [`LanguageServer`] failed to initialize.
+#[derive(Debug, Event)]
+pub struct LanguageServerFailed {
+    pub entity: Entity,
+    /// The error returned by the server, `None` if the `initialize` request timed out.
+    pub error: Option<JsonRpcError>,
+}
+
+/// A [`LanguageServer`] was shut down and sent the `exit` notification.
+#[derive(Debug, Event)]
+pub struct LanguageServerExited {
+    pub entity: Entity,
+}
+
+/// Send the `initialize` request to newly spawned language servers, including retried ones.
+pub(crate) fn initialize_language_servers(
+    mut query: Query<(Entity, &mut LanguageServer, &mut JsonRpc, &mut Process), Added<Process>>,
+    mut failed_events: EventWriter<LanguageServerFailed>,
+) {
+    for (entity, mut language_server, mut json_rpc, mut process) in query.iter_mut() {
+        let params = language_server.initialize_params();
+        match json_rpc.request(&mut process, "initialize", params) {
+            Ok(id) => language_server.state = LanguageServerState::Initializing(id),
+            Err(error) => {
+                warn!("Failed to initialize language server {entity:?}: {error}");
+                language_server.state = LanguageServerState::Failed;
+                failed_events.write(LanguageServerFailed {
+                    entity,
+                    error: None,
+                });
+            },
+        }
+    }
+}
+
+/// Advance the handshake of language servers with the responses to `initialize` and `shutdown`.
+pub(crate) fn handle_language_server_responses(
+    mut query: Query<(&mut LanguageServer, &mut JsonRpc, &mut Process)>,
+    mut response_events: EventReader<JsonRpcResponse>,
+    mut timeout_events: EventReader<JsonRpcTimeoutEvent>,
+    mut ready_events: EventWriter<LanguageServerReady>,
+    mut failed_events: EventWriter<LanguageServerFailed>,
+    mut exited_events: EventWriter<LanguageServerExited>,
+) {
+    let responses = response_events
+        .read()
+        .map(|response| (response.entity, response.id, Some(&response.result)));
+    let timeouts = timeout_events
+        .read()
+        .map(|timeout| (timeout.entity, timeout.id, None));
+
+    for (entity, id, result) in responses.chain(timeouts) {
+        let Ok((mut language_server, mut json_rpc, mut process)) = query.get_mut(entity) else {
+            continue;
+        };
+        match (language_server.state, result) {
+            (LanguageServerState::Initializing(initialize_id), Some(Ok(result)))
+                if initialize_id == id =>
+            {
+                if let Err(error) = json_rpc.notify(&mut process, "initialized", json!({})) {
+                    warn!("Failed to notify language server {entity:?}: {error}");
+                }
+                language_server.state = LanguageServerState::Ready;
+                ready_events.write(LanguageServerReady {
+                    entity,
+                    capabilities: result["capabilities"].clone(),
+                    server_info: result["serverInfo"].clone(),
+                });
+            },
+            (LanguageServerState::Initializing(initialize_id), result) if initialize_id == id => {
+                warn!("Language server {entity:?} failed to initialize");
+                language_server.state = LanguageServerState::Failed;
+                failed_events.write(LanguageServerFailed {
+                    entity,
+                    error: result.and_then(|result| result.clone().err()),
+                });
+            },
+            // The server exits even if the shutdown request failed or timed out
+            (LanguageServerState::ShuttingDown(shutdown_id), _) if shutdown_id == id => {
+                if let Err(error) = json_rpc.notify(&mut process, "exit", Value::Null) {
+                    warn!("Failed to send exit to language server {entity:?}: {error}");
+                }
+                language_server.state = LanguageServerState::Exited;
+                exited_events.write(LanguageServerExited { entity });
+            },
+            _ => {},
+        }
+    }
+}
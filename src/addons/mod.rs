@@ -1,4 +1,18 @@
+pub mod capture;
 pub mod chain;
 pub mod cleanup;
+#[cfg(feature = "ui")]
+pub mod console;
 pub mod delay;
+pub mod history;
+pub mod interactive;
+#[cfg(feature = "serde")]
+pub mod json_lines;
+#[cfg(feature = "serde")]
+pub mod json_rpc;
+pub mod log_output;
+pub mod log_to_file;
+#[cfg(feature = "serde")]
+pub mod lsp;
 pub mod retry;
+pub mod timeout;
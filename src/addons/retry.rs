@@ -1,50 +1,376 @@
 use bevy::prelude::*;
+use bevy_log::{info, tracing::Span};
+use std::{fmt::Debug, process::ExitStatus, sync::Arc, time::Duration};
 
-use crate::{process::Process, LocalCommand, LocalCommandState};
+use crate::{
+    process::Process, Delay, LocalCommand, LocalCommandState, OutputStream, ProcessOutput,
+};
 
+/// Retry failed processes.
+///
+/// Processes are retried when they exit with a non-zero code or time out. Processes killed by a
+/// signal (like a crash or an out-of-memory kill) are not retried, unless the
+/// [`RetryPolicy::retry_if`] condition says so, e.g. with [`RetryCondition::Signaled`].
 #[derive(Debug, Component)]
 pub enum Retry {
+    /// Retry the process right away, at most this many times.
     Attempts(usize),
+    /// Retry the process according to a policy, e.g. with a backoff delay between attempts.
+    Policy(RetryPolicy),
+}
+
+impl Retry {
+    /// Whether the failed attempt should be retried, if there are retries left.
+    fn should_retry(&self, context: &RetryContext) -> bool {
+        match self {
+            Retry::Attempts(_) => RetryCondition::Failure.matches(context),
+            Retry::Policy(policy) => policy.condition.matches(context),
+        }
+    }
+
+    /// Count a new attempt, returning the backoff delay to wait for before it.
+    ///
+    /// Returns [`None`] if there are no retries left.
+    fn next_attempt(&mut self) -> Option<Duration> {
+        match self {
+            Retry::Attempts(retries) => {
+                *retries = retries.checked_sub(1)?;
+                Some(Duration::ZERO)
+            },
+            Retry::Policy(policy) => policy.next_attempt(),
+        }
+    }
+
+    fn retries_left(&self) -> usize {
+        match self {
+            Retry::Attempts(retries) => *retries,
+            Retry::Policy(policy) => policy.attempts,
+        }
+    }
+}
+
+impl From<RetryPolicy> for Retry {
+    fn from(policy: RetryPolicy) -> Self {
+        Retry::Policy(policy)
+    }
+}
+
+/// How long to wait before retrying a failed process.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Backoff {
+    /// Retry right away.
+    #[default]
+    None,
+    /// Wait the same duration before each retry.
+    Fixed(Duration),
+    /// Wait a duration that grows by the same amount with each retry: 1x, 2x, 3x...
+    Linear(Duration),
+    /// Wait a duration that is multiplied with each retry: initial, initial * multiplier,
+    /// initial * multiplier², ...
+    Exponential { initial: Duration, multiplier: f32 },
+}
+
+impl Backoff {
+    /// The delay before the given retry, starting at 1.
+    fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Linear(delay) => delay.saturating_mul(retry),
+            Backoff::Exponential {
+                initial,
+                multiplier,
+            } => Duration::try_from_secs_f64(
+                initial.as_secs_f64() * f64::from(multiplier).powf(f64::from(retry - 1)),
+            )
+            .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+/// Retry settings for failed processes.
+///
+/// ```
+/// use std::time::Duration;
+/// use bevy_local_commands::{Backoff, Retry, RetryPolicy};
+///
+/// // Retry up to 5 times, waiting about 0.5s, 1s, 2s, 4s and 5s (+/- 10%, at most 5s) between
+/// // attempts
+/// let retry: Retry = RetryPolicy::new(5)
+///     .backoff(Backoff::Exponential {
+///         initial: Duration::from_millis(500),
+///         multiplier: 2.0,
+///     })
+///     .max_delay(Duration::from_secs(5))
+///     .jitter(0.1)
+///     .into();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    attempts: usize,
+    backoff: Backoff,
+    max_delay: Option<Duration>,
+    jitter: f32,
+    retries: u32,
+    condition: RetryCondition,
+    stdout: String,
+    stderr: String,
+}
+
+impl RetryPolicy {
+    /// Retry the process at most this many times, right away.
+    pub fn new(attempts: usize) -> Self {
+        Self {
+            attempts,
+            backoff: Backoff::None,
+            max_delay: None,
+            jitter: 0.0,
+            retries: 0,
+            condition: RetryCondition::Failure,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Wait before each retry according to the backoff.
+    ///
+    /// When the backoff delay is not zero, it replaces the [`Delay`] of the retried process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplier of [`Backoff::Exponential`] is negative, infinite or NaN.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        if let Backoff::Exponential { multiplier, .. } = backoff {
+            assert!(
+                multiplier.is_finite() && multiplier >= 0.0,
+                "the backoff multiplier must be finite and non-negative, got {multiplier}"
+            );
+        }
+        self.backoff = backoff;
+        self
+    }
+
+    /// Never wait longer than this before a retry, jitter included.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    /// Randomly spread the backoff delay by up to this fraction of it, in both directions.
+    ///
+    /// A jitter of `0.1` makes a 1s delay anything between 0.9s and 1.1s.
+    /// This avoids many failing processes retrying all at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jitter is not between `0.0` and `1.0`.
+    pub fn jitter(mut self, jitter: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&jitter),
+            "the jitter must be between 0.0 and 1.0, got {jitter}"
+        );
+        self.jitter = jitter;
+        self
+    }
+
+    /// Only retry failed attempts matching the condition.
+    ///
+    /// Defaults to [`RetryCondition::Failure`].
+    pub fn retry_if(mut self, condition: RetryCondition) -> Self {
+        self.condition = condition;
+        self
+    }
+
+    fn next_attempt(&mut self) -> Option<Duration> {
+        self.stdout.clear();
+        self.stderr.clear();
+        self.attempts = self.attempts.checked_sub(1)?;
+        self.retries += 1;
+
+        let mut delay = self.backoff.delay(self.retries);
+        if let Some(max_delay) = self.max_delay {
+            delay = delay.min(max_delay);
+        }
+        if self.jitter > 0.0 {
+            let factor = 1.0 + f64::from(self.jitter) * (fastrand::f64() * 2.0 - 1.0);
+            // Saturate instead of panicking on overflow, e.g. for a delay already at the maximum
+            delay =
+                Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(Duration::MAX);
+            // Jitter must not push the delay past the maximum either
+            if let Some(max_delay) = self.max_delay {
+                delay = delay.min(max_delay);
+            }
+        }
+        Some(delay)
+    }
+}
+
+/// Which failed attempts are retried.
+///
+/// ```
+/// use bevy_local_commands::{RetryCondition, RetryPolicy};
+///
+/// // Exit code 2 means bad input, which is not worth retrying
+/// let policy = RetryPolicy::new(3).retry_if(RetryCondition::custom(|context| {
+///     context.exit_status.and_then(|status| status.code()) != Some(2)
+/// }));
+/// ```
+#[derive(Clone, Default)]
+pub enum RetryCondition {
+    /// Retry processes that exited with a non-zero code or timed out.
+    #[default]
+    Failure,
+    /// Retry processes that exited with one of these codes.
+    ExitCodes(Vec<i32>),
+    /// Retry processes that were killed by a signal, other than through [`Process`].
+    ///
+    /// Such processes are not retried by the other conditions.
+    Signaled,
+    /// Retry processes that failed to spawn.
+    SpawnFailure,
+    /// Retry processes for which the function returns true.
+    Custom(Arc<dyn Fn(&RetryContext) -> bool + Send + Sync>),
+}
+
+impl RetryCondition {
+    /// Retry processes for which the function returns true.
+    pub fn custom(condition: impl Fn(&RetryContext) -> bool + Send + Sync + 'static) -> Self {
+        RetryCondition::Custom(Arc::new(condition))
+    }
+
+    fn matches(&self, context: &RetryContext) -> bool {
+        let code = context.exit_status.and_then(|status| status.code());
+        match self {
+            RetryCondition::Failure => code.is_some() || context.timed_out,
+            RetryCondition::ExitCodes(codes) => code.is_some_and(|code| codes.contains(&code)),
+            RetryCondition::Signaled => {
+                context.exit_status.is_some() && code.is_none() && !context.timed_out
+            },
+            RetryCondition::SpawnFailure => context.exit_status.is_none(),
+            RetryCondition::Custom(condition) => condition(context),
+        }
+    }
+}
+
+impl Debug for RetryCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryCondition::Failure => write!(f, "Failure"),
+            RetryCondition::ExitCodes(codes) => f.debug_tuple("ExitCodes").field(codes).finish(),
+            RetryCondition::Signaled => write!(f, "Signaled"),
+            RetryCondition::SpawnFailure => write!(f, "SpawnFailure"),
+            RetryCondition::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// What is known about a failed attempt, to decide whether to retry it.
+#[derive(Debug)]
+pub struct RetryContext<'a> {
+    /// The exit status of the process, or [`None`] if it failed to spawn.
+    pub exit_status: Option<ExitStatus>,
+    /// Whether the process was killed by the [`Timeout`](crate::Timeout) addon.
+    pub timed_out: bool,
+    /// The whole stdout of the attempt.
+    ///
+    /// Only captured for [`RetryCondition::Custom`], empty otherwise.
+    pub stdout: &'a str,
+    /// The whole stderr of the attempt.
+    ///
+    /// Only captured for [`RetryCondition::Custom`], empty otherwise.
+    pub stderr: &'a str,
 }
 
 #[derive(Debug, Event)]
 pub struct RetryEvent {
     pub entity: Entity,
     pub retries_left: usize,
+    /// How long until the process is spawned again.
+    pub delay: Duration,
+}
+
+/// Keep the output of the current attempt for custom retry conditions.
+pub(crate) fn capture_retry_output(
+    mut query: Query<&mut Retry>,
+    mut process_output_events: EventReader<ProcessOutput>,
+) {
+    for output in process_output_events.read() {
+        let Ok(mut retry) = query.get_mut(output.entity) else {
+            continue;
+        };
+        if let Retry::Policy(policy) = &mut *retry {
+            if let RetryCondition::Custom(_) = policy.condition {
+                let captured = match output.stream {
+                    OutputStream::Stdout => &mut policy.stdout,
+                    OutputStream::Stderr => &mut policy.stderr,
+                };
+                captured.push_str(&output.output);
+            }
+        }
+    }
 }
 
 /// Retry failed processes according to the Retry component.
 ///
 /// Processes without the Retry component are ignored.
+/// Failed attempts that do not match the retry condition are not retried.
 /// The Retry component is removed from the entity when retries are done.
+///
+/// Each retry is logged as an event of the `command` span of the process.
 pub(crate) fn retry_failed_process(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut LocalCommand, &mut Retry), With<Process>>,
+    mut query: Query<(
+        Entity,
+        &mut LocalCommand,
+        &mut Retry,
+        Option<&mut Process>,
+        Option<&Delay>,
+    )>,
     mut retry_events: EventWriter<RetryEvent>,
 ) {
-    for (entity, mut local_command, mut retry) in query.iter_mut() {
+    for (entity, mut local_command, mut retry, process, delay) in query.iter_mut() {
         if local_command.state == LocalCommandState::Error {
-            match &mut *retry {
-                Retry::Attempts(retries) => {
-                    if let Ok(mut entity_commands) = commands.get_entity(entity) {
-                        if *retries < 1 {
-                            entity_commands.remove::<Retry>();
-                            continue;
-                        }
-
-                        // Update the retry attempts
-                        *retries -= 1;
-
-                        // Spawn the process once again
-                        commands.entity(entity).remove::<Process>();
-                        local_command.delay = None;
-                        local_command.state = LocalCommandState::Ready;
-                        retry_events.write(RetryEvent {
-                            entity,
-                            retries_left: *retries,
-                        });
-                    }
-                },
+            let (stdout, stderr) = match &*retry {
+                Retry::Policy(policy) => (policy.stdout.as_str(), policy.stderr.as_str()),
+                Retry::Attempts(_) => ("", ""),
+            };
+            let context = RetryContext {
+                exit_status: process.and_then(|mut process| process.process.try_wait().ok()?),
+                timed_out: local_command.timed_out,
+                stdout,
+                stderr,
+            };
+            if !retry.should_retry(&context) {
+                continue;
+            }
+
+            if let Ok(mut entity_commands) = commands.get_entity(entity) {
+                let Some(backoff) = retry.next_attempt() else {
+                    entity_commands.remove::<Retry>();
+                    continue;
+                };
+
+                // Spawn the process once again
+                entity_commands.remove::<Process>();
+                local_command.reset_attempt();
+                let delay = if backoff.is_zero() {
+                    // The Delay addon applies as usual
+                    delay.map(Delay::duration).unwrap_or_default()
+                } else {
+                    local_command.delay = Some(Timer::new(backoff, TimerMode::Once));
+                    backoff
+                };
+                info!(
+                    parent: local_command.span.as_ref().and_then(Span::id),
+                    retries_left = retry.retries_left(),
+                    ?delay,
+                    "Retrying process"
+                );
+                retry_events.write(RetryEvent {
+                    entity,
+                    retries_left: retry.retries_left(),
+                    delay,
+                });
             }
         }
     }
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_log::warn;
+use std::time::Duration;
+
+use crate::{process::Process, LocalCommand, LocalCommandState};
+
+#[derive(Debug, Component)]
+pub enum Timeout {
+    After(Duration),
+}
+
+#[derive(Debug, Event)]
+pub struct TimeoutEvent {
+    pub entity: Entity,
+    pub timeout: Duration,
+}
+
+/// Kill running processes that exceeded the duration of their Timeout component.
+///
+/// The timeout is measured in real time and restarts with each retry.
+/// The killed process moves to LocalCommandState::Error, which allows for retry logic to kick in.
+pub(crate) fn apply_timeout(
+    mut query: Query<(Entity, &mut LocalCommand, &mut Process, &Timeout)>,
+    mut timeout_events: EventWriter<TimeoutEvent>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut local_command, mut process, timeout) in query.iter_mut() {
+        if local_command.state != LocalCommandState::Running || local_command.timed_out {
+            continue;
+        }
+        match timeout {
+            Timeout::After(duration) => {
+                let timer = local_command
+                    .timeout
+                    .get_or_insert_with(|| Timer::new(*duration, TimerMode::Once));
+                timer.tick(time.delta());
+                if !timer.finished() {
+                    continue;
+                }
+
+                warn!(
+                    "Process with pid {} timed out after {:?}",
+                    process.id(),
+                    duration
+                );
+                if let Err(error) = process.kill() {
+                    warn!("Failed to kill process with pid {}: {error}", process.id());
+                }
+                local_command.timed_out = true;
+                timeout_events.write(TimeoutEvent {
+                    entity,
+                    timeout: *duration,
+                });
+            },
+        }
+    }
+}
@@ -0,0 +1,313 @@
+/// A color set by an ANSI escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// A color of the 256 color palette, whose first 16 colors are the named ones.
+    Indexed(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    const NAMED: [AnsiColor; 16] = [
+        AnsiColor::Black,
+        AnsiColor::Red,
+        AnsiColor::Green,
+        AnsiColor::Yellow,
+        AnsiColor::Blue,
+        AnsiColor::Magenta,
+        AnsiColor::Cyan,
+        AnsiColor::White,
+        AnsiColor::BrightBlack,
+        AnsiColor::BrightRed,
+        AnsiColor::BrightGreen,
+        AnsiColor::BrightYellow,
+        AnsiColor::BrightBlue,
+        AnsiColor::BrightMagenta,
+        AnsiColor::BrightCyan,
+        AnsiColor::BrightWhite,
+    ];
+
+    /// The red, green and blue components of the color, using the xterm palette.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::AnsiColor;
+    ///
+    /// assert_eq!(AnsiColor::Red.to_rgb(), [205, 0, 0]);
+    /// assert_eq!(AnsiColor::Indexed(196).to_rgb(), [255, 0, 0]);
+    /// assert_eq!(AnsiColor::Indexed(232).to_rgb(), [8, 8, 8]);
+    /// ```
+    pub fn to_rgb(self) -> [u8; 3] {
+        const NAMED_RGB: [[u8; 3]; 16] = [
+            [0, 0, 0],
+            [205, 0, 0],
+            [0, 205, 0],
+            [205, 205, 0],
+            [0, 0, 238],
+            [205, 0, 205],
+            [0, 205, 205],
+            [229, 229, 229],
+            [127, 127, 127],
+            [255, 0, 0],
+            [0, 255, 0],
+            [255, 255, 0],
+            [92, 92, 255],
+            [255, 0, 255],
+            [0, 255, 255],
+            [255, 255, 255],
+        ];
+
+        match self {
+            AnsiColor::Rgb(red, green, blue) => [red, green, blue],
+            AnsiColor::Indexed(index @ 0..=15) => NAMED_RGB[index as usize],
+            // 6x6x6 color cube
+            AnsiColor::Indexed(index @ 16..=231) => {
+                let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+                let index = index - 16;
+                [level(index / 36), level(index / 6 % 6), level(index % 6)]
+            },
+            // Grayscale ramp
+            AnsiColor::Indexed(index) => {
+                let gray = 8 + (index - 232) * 10;
+                [gray, gray, gray]
+            },
+            named => {
+                NAMED_RGB[Self::NAMED
+                    .iter()
+                    .position(|color| *color == named)
+                    .unwrap_or(0)]
+            },
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        Self::NAMED
+            .get(index as usize)
+            .copied()
+            .unwrap_or(AnsiColor::Indexed(index))
+    }
+}
+
+#[cfg(feature = "ui")]
+impl From<AnsiColor> for bevy::color::Color {
+    fn from(color: AnsiColor) -> Self {
+        let [red, green, blue] = color.to_rgb();
+        bevy::color::Color::srgb_u8(red, green, blue)
+    }
+}
+
+/// The style of a piece of text, as set by ANSI escape sequences.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AnsiStyle {
+    /// The text color, `None` for the default color.
+    pub foreground: Option<AnsiColor>,
+    /// The background color, `None` for the default color.
+    pub background: Option<AnsiColor>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl AnsiStyle {
+    /// Apply the parameters of a Select Graphic Rendition (`ESC[...m`) sequence.
+    fn apply_sgr(&mut self, params: &str) {
+        let mut params = params
+            .split([';', ':'])
+            .map(|param| param.parse::<u16>().unwrap_or(0));
+        // An empty sequence is a reset
+        let mut next = params.next();
+        while let Some(param) = next {
+            match param {
+                0 => *self = AnsiStyle::default(),
+                1 => self.bold = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => self.bold = false,
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.foreground = Some(AnsiColor::from_index(param as u8 - 30)),
+                38 => self.foreground = extended_color(&mut params),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(AnsiColor::from_index(param as u8 - 40)),
+                48 => self.background = extended_color(&mut params),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(AnsiColor::from_index(param as u8 - 90 + 8)),
+                100..=107 => self.background = Some(AnsiColor::from_index(param as u8 - 100 + 8)),
+                _ => {},
+            }
+            next = params.next();
+        }
+    }
+}
+
+/// Parse the color of a `38` or `48` parameter: `5;index` or `2;red;green;blue`.
+fn extended_color(params: &mut impl Iterator<Item = u16>) -> Option<AnsiColor> {
+    let mut component = || params.next().map(|value| value.min(255) as u8);
+    match component()? {
+        5 => Some(AnsiColor::from_index(component()?)),
+        2 => Some(AnsiColor::Rgb(component()?, component()?, component()?)),
+        _ => None,
+    }
+}
+
+/// A piece of text with a single style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub style: AnsiStyle,
+}
+
+/// Splits text containing ANSI escape sequences into [`StyledSpan`]s.
+///
+/// The style carries over from one call to the next, so that colors spanning several
+/// [`ProcessOutput`](crate::ProcessOutput) events are kept. Escape sequences other than colors
+/// and text attributes (like cursor movements) are removed.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_local_commands::{AnsiColor, AnsiParser};
+///
+/// let mut parser = AnsiParser::default();
+/// let spans = parser.parse("\x1b[1;31merror\x1b[0m: mismatched types");
+/// assert_eq!(spans[0].text, "error");
+/// assert_eq!(spans[0].style.foreground, Some(AnsiColor::Red));
+/// assert!(spans[0].style.bold);
+/// assert_eq!(spans[1].text, ": mismatched types");
+/// assert_eq!(spans[1].style, Default::default());
+///
+/// // The style is kept until it is reset
+/// let spans = parser.parse("\x1b[32mok");
+/// let spans = parser.parse("still green");
+/// assert_eq!(spans[0].style.foreground, Some(AnsiColor::Green));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnsiParser {
+    style: AnsiStyle,
+}
+
+impl AnsiParser {
+    /// The style that applies to the next text.
+    pub fn style(&self) -> AnsiStyle {
+        self.style
+    }
+
+    /// Split the text into spans, merging consecutive text with the same style.
+    pub fn parse(&mut self, text: &str) -> Vec<StyledSpan> {
+        let mut spans: Vec<StyledSpan> = Vec::new();
+        for token in Tokens(text) {
+            match token {
+                Token::Text(text) => match spans.last_mut() {
+                    Some(span) if span.style == self.style => span.text.push_str(text),
+                    _ => spans.push(StyledSpan {
+                        text: text.to_owned(),
+                        style: self.style,
+                    }),
+                },
+                Token::Sgr(params) => self.style.apply_sgr(params),
+                Token::Other => {},
+            }
+        }
+        spans
+    }
+}
+
+/// Split text containing ANSI escape sequences into [`StyledSpan`]s, starting with the default
+/// style.
+///
+/// See [`AnsiParser`] to keep the style across several pieces of text.
+pub fn parse_ansi(text: &str) -> Vec<StyledSpan> {
+    AnsiParser::default().parse(text)
+}
+
+/// Remove all the ANSI escape sequences from the text.
+///
+/// # Examples
+///
+/// ```
+/// use bevy_local_commands::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: \x1b]0;title\x07failed"), "error: failed");
+/// assert_eq!(strip_ansi("no escapes"), "no escapes");
+/// ```
+pub fn strip_ansi(text: &str) -> String {
+    Tokens(text)
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    /// The parameters of a Select Graphic Rendition sequence.
+    Sgr(&'a str),
+    /// Any other escape sequence.
+    Other,
+}
+
+/// Iterates over the text and escape sequences of a string.
+struct Tokens<'a>(&'a str);
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let text = self.0;
+        if text.is_empty() {
+            return None;
+        }
+        if !text.starts_with('\x1b') {
+            let end = text.find('\x1b').unwrap_or(text.len());
+            self.0 = &text[end..];
+            return Some(Token::Text(&text[..end]));
+        }
+
+        let rest = &text[1..];
+        let (token, length) = match rest.chars().next() {
+            // Control Sequence Introducer: parameters, then a final byte in @..~
+            Some('[') => {
+                let body = &rest[1..];
+                match body.find(|c: char| ('@'..='~').contains(&c)) {
+                    Some(end) if body[end..].starts_with('m') => {
+                        (Token::Sgr(&body[..end]), end + 2)
+                    },
+                    Some(end) => (Token::Other, end + 2),
+                    None => (Token::Other, rest.len()),
+                }
+            },
+            // Operating System Command: ends with BEL or ESC \
+            Some(']') => {
+                let body = &rest[1..];
+                match (body.find('\x07'), body.find("\x1b\\")) {
+                    (Some(bel), Some(st)) if st < bel => (Token::Other, st + 3),
+                    (Some(bel), _) => (Token::Other, bel + 2),
+                    (None, Some(st)) => (Token::Other, st + 3),
+                    (None, None) => (Token::Other, rest.len()),
+                }
+            },
+            Some(c) => (Token::Other, c.len_utf8()),
+            None => (Token::Other, 0),
+        };
+        self.0 = &rest[length..];
+        Some(token)
+    }
+}
@@ -0,0 +1,233 @@
+use std::io;
+
+/// Splits the raw output of a process into frames, sent as
+/// [`ProcessOutputFrame`](crate::ProcessOutputFrame) events.
+///
+/// The decoder runs on the thread reading the stream, fed with the output as it is read.
+/// See [`LocalCommand::decoder`](crate::LocalCommand::decoder).
+///
+/// Here's a decoder for messages separated by a semicolon:
+///
+/// ```
+/// use std::io;
+/// use bevy_local_commands::OutputDecoder;
+///
+/// #[derive(Clone)]
+/// struct SemicolonDecoder;
+///
+/// impl OutputDecoder for SemicolonDecoder {
+///     fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+///         let Some(end) = buffer.iter().position(|byte| *byte == b';') else {
+///             return Ok(None);
+///         };
+///         let frame = buffer.drain(..=end).take(end).collect();
+///         Ok(Some(frame))
+///     }
+/// }
+///
+/// let mut buffer = b"first;second;thi".to_vec();
+/// let mut decoder = SemicolonDecoder;
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"first".to_vec()));
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"second".to_vec()));
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), None);
+/// assert_eq!(buffer, b"thi");
+/// ```
+pub trait OutputDecoder: Send + 'static {
+    /// Removes the first complete frame from the buffer and returns it.
+    ///
+    /// Returns [`None`] if the buffer does not hold a complete frame yet.
+    /// Errors stop the reading of the output.
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>>;
+
+    /// Same as [`OutputDecoder::decode`], once the output is closed and nothing more will be
+    /// added to the buffer.
+    ///
+    /// Called until it returns [`None`]. By default, leftover bytes are returned as a last frame.
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        match self.decode(buffer)? {
+            None if !buffer.is_empty() => Ok(Some(std::mem::take(buffer))),
+            frame => Ok(frame),
+        }
+    }
+}
+
+/// Removes the frame ending with the delimiter from the start of the buffer.
+fn decode_delimited(buffer: &mut Vec<u8>, delimiter: u8) -> Option<Vec<u8>> {
+    let end = buffer.iter().position(|byte| *byte == delimiter)?;
+    let mut frame: Vec<u8> = buffer.drain(..=end).collect();
+    frame.pop();
+    Some(frame)
+}
+
+/// The error for output that ends in the middle of a frame.
+fn truncated_frame() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "output ended in the middle of a frame",
+    )
+}
+
+/// Frames are lines, without the trailing `\n` or `\r\n`.
+///
+/// ```
+/// use bevy_local_commands::{LinesDecoder, OutputDecoder};
+///
+/// let mut buffer = b"first\r\nsecond\n".to_vec();
+/// assert_eq!(LinesDecoder.decode(&mut buffer).unwrap(), Some(b"first".to_vec()));
+/// assert_eq!(LinesDecoder.decode(&mut buffer).unwrap(), Some(b"second".to_vec()));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinesDecoder;
+
+impl OutputDecoder for LinesDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        Ok(decode_delimited(buffer, b'\n').map(|mut line| {
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            line
+        }))
+    }
+}
+
+/// Frames are separated by NUL bytes (`\0`), as with `find -print0`.
+///
+/// ```
+/// use bevy_local_commands::{NulDelimitedDecoder, OutputDecoder};
+///
+/// let mut buffer = b"first\0second\0".to_vec();
+/// assert_eq!(NulDelimitedDecoder.decode(&mut buffer).unwrap(), Some(b"first".to_vec()));
+/// assert_eq!(NulDelimitedDecoder.decode(&mut buffer).unwrap(), Some(b"second".to_vec()));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NulDelimitedDecoder;
+
+impl OutputDecoder for NulDelimitedDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        Ok(decode_delimited(buffer, b'\0'))
+    }
+}
+
+/// Frames all have the same length in bytes.
+///
+/// Output ending in the middle of a frame is reported as an error.
+///
+/// ```
+/// use bevy_local_commands::{FixedLengthDecoder, OutputDecoder};
+///
+/// let mut buffer = b"abcdef".to_vec();
+/// let mut decoder = FixedLengthDecoder(4);
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"abcd".to_vec()));
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), None);
+/// assert!(decoder.decode_eof(&mut buffer).is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FixedLengthDecoder(pub usize);
+
+impl OutputDecoder for FixedLengthDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        if self.0 == 0 || buffer.len() < self.0 {
+            return Ok(None);
+        }
+        Ok(Some(buffer.drain(..self.0).collect()))
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        match self.decode(buffer)? {
+            None if !buffer.is_empty() => Err(truncated_frame()),
+            frame => Ok(frame),
+        }
+    }
+}
+
+/// Frames are prefixed by their length in bytes, as a 4 bytes big-endian integer.
+///
+/// The prefix is not part of the frame. Output ending in the middle of a frame is reported as an
+/// error.
+///
+/// ```
+/// use bevy_local_commands::{LengthPrefixedDecoder, OutputDecoder};
+///
+/// let mut buffer = b"\0\0\0\x05hello\0\0\0".to_vec();
+/// let mut decoder = LengthPrefixedDecoder;
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"hello".to_vec()));
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LengthPrefixedDecoder;
+
+impl OutputDecoder for LengthPrefixedDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        let Some(prefix) = buffer.first_chunk::<4>() else {
+            return Ok(None);
+        };
+        let length = u32::from_be_bytes(*prefix) as usize;
+        if buffer.len() < 4 + length {
+            return Ok(None);
+        }
+        Ok(Some(buffer.drain(..4 + length).skip(4).collect()))
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        match self.decode(buffer)? {
+            None if !buffer.is_empty() => Err(truncated_frame()),
+            frame => Ok(frame),
+        }
+    }
+}
+
+/// Frames are preceded by headers, including a `Content-Length` header with the length of the
+/// frame in bytes, as used by the Language Server Protocol.
+///
+/// The headers are not part of the frame. Output ending in the middle of a frame, or headers
+/// without a valid `Content-Length`, are reported as errors.
+///
+/// ```
+/// use bevy_local_commands::{ContentLengthDecoder, OutputDecoder};
+///
+/// let mut buffer = b"Content-Length: 2\r\nContent-Type: application/json\r\n\r\n{}".to_vec();
+/// let mut decoder = ContentLengthDecoder;
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"{}".to_vec()));
+/// assert!(buffer.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContentLengthDecoder;
+
+impl OutputDecoder for ContentLengthDecoder {
+    fn decode(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        let Some(headers_end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") else {
+            return Ok(None);
+        };
+        let headers = std::str::from_utf8(&buffer[..headers_end])
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let length = headers
+            .split("\r\n")
+            .filter_map(|header| header.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("Content-Length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "missing or invalid Content-Length header",
+                )
+            })?;
+
+        let body_start = headers_end + 4;
+        if buffer.len() < body_start + length {
+            return Ok(None);
+        }
+        Ok(Some(
+            buffer
+                .drain(..body_start + length)
+                .skip(body_start)
+                .collect(),
+        ))
+    }
+
+    fn decode_eof(&mut self, buffer: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        match self.decode(buffer)? {
+            None if !buffer.is_empty() => Err(truncated_frame()),
+            frame => Ok(frame),
+        }
+    }
+}
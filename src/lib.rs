@@ -1,28 +1,78 @@
+use std::ffi::OsString;
+use std::io;
+use std::path::PathBuf;
 use std::process::ExitStatus;
 use std::str::Lines;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 
 use bevy::prelude::*;
 
 mod addons;
+mod ansi;
+mod decoder;
 mod local_command;
 mod process;
+#[cfg(unix)]
+mod pty;
+#[cfg(unix)]
+mod signal;
 mod systems;
 
+pub use addons::capture::{CaptureOutput, ProcessFinishedWithOutput};
 pub use addons::chain::{Chain, ChainCompletedEvent};
 pub use addons::cleanup::Cleanup;
+#[cfg(feature = "ui")]
+pub use addons::console::ProcessConsole;
 pub use addons::delay::Delay;
-pub use addons::retry::{Retry, RetryEvent};
-pub use local_command::{LocalCommand, LocalCommandDone, LocalCommandState};
-pub use process::Process;
+pub use addons::history::{HistoryLine, OutputHistory};
+pub use addons::interactive::{Interactive, PromptId, ReplyReceived, Terminator};
+#[cfg(feature = "serde")]
+pub use addons::json_lines::{
+    JsonLinesOutput, JsonLinesParseError, JsonLinesPlugin, ProcessMessage,
+};
+#[cfg(feature = "serde")]
+pub use addons::json_rpc::{
+    JsonRpc, JsonRpcError, JsonRpcFraming, JsonRpcId, JsonRpcNotification, JsonRpcResponse,
+    JsonRpcServerRequest, JsonRpcTimeoutEvent,
+};
+pub use addons::log_output::LogOutput;
+pub use addons::log_to_file::LogToFile;
+#[cfg(feature = "serde")]
+pub use addons::lsp::{
+    LanguageServer, LanguageServerExited, LanguageServerFailed, LanguageServerReady,
+    LanguageServerState,
+};
+pub use addons::retry::{Backoff, Retry, RetryCondition, RetryContext, RetryEvent, RetryPolicy};
+pub use addons::timeout::{Timeout, TimeoutEvent};
+pub use ansi::{parse_ansi, strip_ansi, AnsiColor, AnsiParser, AnsiStyle, StyledSpan};
+pub use decoder::{
+    ContentLengthDecoder, FixedLengthDecoder, LengthPrefixedDecoder, LinesDecoder,
+    NulDelimitedDecoder, OutputDecoder,
+};
+pub use local_command::{LocalCommand, LocalCommandDone, LocalCommandState, OutputMode, StdioMode};
+pub use process::{DropPolicy, Process, ProcessTiming};
+#[cfg(unix)]
+pub use pty::PtySize;
+#[cfg(unix)]
+pub use signal::Signal;
 
 /// The ID of a process.
 pub type Pid = u32;
 
+/// The standard stream a piece of process output was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
 #[derive(Debug, Event)]
 pub struct ProcessOutput {
     pub entity: Entity,
+    /// The stream this output was read from.
+    pub stream: OutputStream,
     /// The output generated in the last frame.
     ///
     /// Has a trailing newline character.
@@ -43,9 +93,101 @@ impl ProcessOutput {
     }
 }
 
+/// The raw output of a process using [`OutputMode::Bytes`].
+#[derive(Debug, Event)]
+pub struct ProcessOutputBytes {
+    pub entity: Entity,
+    /// The stream this output was read from.
+    pub stream: OutputStream,
+    /// The output generated in the last frame.
+    bytes: Vec<u8>,
+}
+
+impl ProcessOutputBytes {
+    /// The bytes generated by the program in the last frame.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// A frame of the output of a process using an [`OutputDecoder`].
+///
+/// See [`LocalCommand::decoder`] for stdout, and [`LocalCommand::stderr_decoder`] for stderr.
+#[derive(Debug, Event)]
+pub struct ProcessOutputFrame {
+    pub entity: Entity,
+    /// The stream this frame was read from.
+    pub stream: OutputStream,
+    frame: Vec<u8>,
+}
+
+impl ProcessOutputFrame {
+    /// The bytes of the frame.
+    pub fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+
+    /// The frame as a string slice, if it is valid UTF-8.
+    pub fn text(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.frame)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ProcessErrorInfo {
-    FailedToStart,
+    /// The process could not be spawned, e.g. because the program was not found.
+    FailedToStart {
+        kind: io::ErrorKind,
+        /// The error code from the operating system, if any.
+        os_error: Option<i32>,
+        message: String,
+        program: OsString,
+        current_dir: Option<PathBuf>,
+    },
+    /// Writing to the process stdin failed, e.g. because the process closed it.
+    StdinWriteFailed {
+        kind: io::ErrorKind,
+        /// The error code from the operating system, if any.
+        os_error: Option<i32>,
+        message: String,
+    },
+    /// Reading one of the process output streams failed, e.g. because the output was not valid
+    /// UTF-8. The rest of the stream is discarded.
+    OutputReadFailed {
+        stream: OutputStream,
+        kind: io::ErrorKind,
+        /// The error code from the operating system, if any.
+        os_error: Option<i32>,
+        message: String,
+    },
+}
+
+impl ProcessErrorInfo {
+    /// The kind of the underlying IO error.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            ProcessErrorInfo::FailedToStart { kind, .. }
+            | ProcessErrorInfo::StdinWriteFailed { kind, .. }
+            | ProcessErrorInfo::OutputReadFailed { kind, .. } => *kind,
+        }
+    }
+
+    pub(crate) fn stdin_write_failed(error: &io::Error) -> Self {
+        ProcessErrorInfo::StdinWriteFailed {
+            kind: error.kind(),
+            os_error: error.raw_os_error(),
+            message: error.to_string(),
+        }
+    }
+
+    pub(crate) fn output_read_failed(stream: OutputStream, error: &io::Error) -> Self {
+        ProcessErrorInfo::OutputReadFailed {
+            stream,
+            kind: error.kind(),
+            os_error: error.raw_os_error(),
+            message: error.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Event)]
@@ -54,37 +196,136 @@ pub struct ProcessError {
     pub info: ProcessErrorInfo,
 }
 
+/// How a process that was asked to stop has ended.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Termination {
+    /// Graceful - Process received the termination signal and exited before the grace period
+    /// elapsed.
+    Graceful,
+    /// Forced - Process was killed, either directly or after the grace period elapsed.
+    Forced,
+}
+
 #[derive(Debug, Event)]
 pub struct ProcessCompleted {
     pub entity: Entity,
     pub exit_status: ExitStatus,
+    /// How the process ended, if it was asked to stop.
+    ///
+    /// See [`Process::stop_gracefully`] and [`Process::kill`].
+    pub termination: Option<Termination>,
+    /// The number of the attempt that completed, starting at 1 and counting retries.
+    pub attempt: u32,
+    /// When the process was spawned.
+    pub spawned_at: SystemTime,
+    /// When the process was seen exiting.
+    pub completed_at: SystemTime,
+    /// How long the process ran, from its spawn until it was seen exiting.
+    pub duration: Duration,
+    /// How long the attempt waited for its [`Delay`] (or retry backoff) before the process was
+    /// spawned.
+    pub delay: Duration,
 }
 
-/// The lines written to the standard output by a given process.
+/// The output written to one of the output streams by a given process.
 #[derive(Debug, Default, Clone)]
-struct ProcessOutputBuffer(Arc<Mutex<String>>);
+struct ProcessOutputBuffer<T = String>(Arc<Mutex<T>>);
+
+impl<T: Default> ProcessOutputBuffer<T> {
+    /// Take the buffered output, leaving the buffer empty.
+    fn take(&self) -> T {
+        self.0
+            .lock()
+            .map(|mut buffer| std::mem::take(&mut *buffer))
+            .unwrap_or_default()
+    }
+}
+
+/// The errors encountered while interacting with a given process, waiting to be sent as events.
+#[derive(Debug, Default, Clone)]
+struct ProcessErrorBuffer(Arc<Mutex<Vec<ProcessErrorInfo>>>);
+
+impl ProcessErrorBuffer {
+    fn push(&self, info: ProcessErrorInfo) {
+        if let Ok(mut errors) = self.0.lock() {
+            errors.push(info);
+        }
+    }
+}
 
 pub struct BevyLocalCommandsPlugin;
 
 impl Plugin for BevyLocalCommandsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ProcessOutput>()
+            .add_event::<ProcessOutputBytes>()
+            .add_event::<ProcessOutputFrame>()
             .add_event::<ProcessCompleted>()
             .add_event::<ProcessError>()
             .add_event::<RetryEvent>()
             .add_event::<ChainCompletedEvent>()
+            .add_event::<TimeoutEvent>()
+            .add_event::<ReplyReceived>()
+            .add_event::<ProcessFinishedWithOutput>()
+            .init_resource::<DropPolicy>()
             .add_systems(PreUpdate, addons::delay::apply_delay)
+            .add_systems(Last, systems::handle_app_exit)
             .add_systems(
                 Update,
                 (
                     systems::handle_new_command,
                     systems::handle_process_output,
+                    addons::log_output::log_process_output,
+                    addons::interactive::collect_interactive_replies,
+                    addons::interactive::send_interactive_prompts,
+                    systems::handle_process_errors,
+                    systems::handle_graceful_stop,
+                    addons::timeout::apply_timeout,
                     systems::handle_completed_process,
+                    addons::capture::capture_process_output,
+                    addons::log_to_file::write_output_to_files,
                     addons::cleanup::cleanup_completed_process,
+                    addons::retry::capture_retry_output,
                     addons::retry::retry_failed_process,
                     addons::chain::chain_execution_system,
                 )
                     .chain(),
             );
+        #[cfg(feature = "ui")]
+        app.add_systems(
+            Update,
+            (
+                addons::console::setup_process_consoles,
+                addons::console::update_process_consoles,
+                // Keyboard input comes from the InputPlugin, part of the DefaultPlugins
+                addons::console::handle_process_console_input
+                    .run_if(resource_exists::<Events<bevy::input::keyboard::KeyboardInput>>),
+            )
+                .chain()
+                .after(systems::handle_process_output)
+                .before(addons::cleanup::cleanup_completed_process),
+        );
+        #[cfg(feature = "serde")]
+        app.add_event::<JsonRpcResponse>()
+            .add_event::<JsonRpcNotification>()
+            .add_event::<JsonRpcServerRequest>()
+            .add_event::<JsonRpcTimeoutEvent>()
+            .add_event::<LanguageServerReady>()
+            .add_event::<LanguageServerFailed>()
+            .add_event::<LanguageServerExited>()
+            .add_observer(addons::json_rpc::on_insert_local_command)
+            .add_systems(
+                Update,
+                (
+                    addons::json_rpc::reset_json_rpc_requests,
+                    addons::lsp::initialize_language_servers,
+                    addons::json_rpc::handle_json_rpc_output,
+                    addons::json_rpc::apply_json_rpc_timeouts,
+                    addons::lsp::handle_language_server_responses,
+                )
+                    .chain()
+                    .after(systems::handle_process_output)
+                    .before(addons::cleanup::cleanup_completed_process),
+            );
     }
 }
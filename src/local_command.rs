@@ -1,17 +1,116 @@
 use std::{
     ffi::OsStr,
     fmt::Debug,
-    path::Path,
-    process::{Command, CommandArgs, CommandEnvs},
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    process::{Command, CommandArgs, CommandEnvs, Stdio},
+    sync::Arc,
+    time::Instant,
 };
 
 use bevy::prelude::*;
+use bevy_log::tracing::Span;
+
+#[cfg(unix)]
+use crate::PtySize;
+use crate::{DropPolicy, OutputDecoder};
+
+/// Creates a new decoder for each spawned process.
+pub(crate) type DecoderFactory = Arc<dyn Fn() -> Box<dyn OutputDecoder> + Send + Sync>;
+
+fn decoder_factory<D: OutputDecoder + Clone + Sync>(decoder: D) -> DecoderFactory {
+    Arc::new(move || Box::new(decoder.clone()))
+}
 
 #[derive(Component)]
 pub struct LocalCommand {
     pub(crate) command: Command,
     pub(crate) delay: Option<Timer>,
+    pub(crate) timeout: Option<Timer>,
+    pub(crate) timed_out: bool,
     pub(crate) state: LocalCommandState,
+    pub(crate) stdin: StdioMode,
+    pub(crate) stdout: StdioMode,
+    pub(crate) stderr: StdioMode,
+    pub(crate) output_mode: OutputMode,
+    pub(crate) decoder: Option<DecoderFactory>,
+    pub(crate) stderr_decoder: Option<DecoderFactory>,
+    #[cfg(unix)]
+    pub(crate) process_group: bool,
+    #[cfg(unix)]
+    pub(crate) pty: Option<PtySize>,
+    pub(crate) drop_policy: Option<DropPolicy>,
+    /// How many times the process was spawned, including the current attempt.
+    pub(crate) attempt: u32,
+    /// The `command` span, covering the whole lifetime of the command (delays and retries
+    /// included) until it is done.
+    pub(crate) span: Option<Span>,
+    /// The `delay` span, while waiting for the delay before spawning the process.
+    pub(crate) delay_span: Option<Span>,
+    /// When the process started waiting for the delay.
+    pub(crate) delay_started: Option<Instant>,
+}
+
+/// Where one of the standard streams of the process is connected to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StdioMode {
+    /// Piped - Stream is connected to Bevy.
+    ///
+    /// Input can be written through [`Process`](crate::Process) and output is sent as
+    /// [`ProcessOutput`](crate::ProcessOutput) events.
+    #[default]
+    Piped,
+    /// Inherit - Stream is shared with the Bevy app, e.g. the terminal it runs in.
+    Inherit,
+    /// Null - Output is discarded and no input is provided.
+    Null,
+    /// File - Input is read from the file, or output is written to it (truncating it first).
+    File(PathBuf),
+}
+
+impl StdioMode {
+    fn input(&self) -> io::Result<Stdio> {
+        match self {
+            StdioMode::File(path) => File::open(path).map(Stdio::from),
+            _ => Ok(self.stdio()),
+        }
+    }
+
+    fn output(&self) -> io::Result<Stdio> {
+        match self {
+            StdioMode::File(path) => File::create(path).map(Stdio::from),
+            _ => Ok(self.stdio()),
+        }
+    }
+
+    fn stdio(&self) -> Stdio {
+        match self {
+            StdioMode::Piped => Stdio::piped(),
+            StdioMode::Inherit => Stdio::inherit(),
+            StdioMode::Null | StdioMode::File(_) => Stdio::null(),
+        }
+    }
+}
+
+/// How the piped output streams of the process are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Text - Output is read line by line as UTF-8 and sent as
+    /// [`ProcessOutput`](crate::ProcessOutput) events.
+    ///
+    /// Reading stops at the first invalid UTF-8 line, which is reported as
+    /// [`ProcessErrorInfo::OutputReadFailed`](crate::ProcessErrorInfo::OutputReadFailed).
+    #[default]
+    Text,
+    /// LossyText - Same as [`OutputMode::Text`], but invalid UTF-8 sequences are replaced with
+    /// `U+FFFD REPLACEMENT CHARACTER` instead.
+    LossyText,
+    /// Bytes - Output is read as raw bytes, in chunks of any size, and sent as
+    /// [`ProcessOutputBytes`](crate::ProcessOutputBytes) events.
+    ///
+    /// Use it for binary output such as images or compressed data.
+    Bytes,
 }
 
 /// Keep track of the state of the running process.
@@ -23,6 +122,8 @@ pub enum LocalCommandState {
     Ready,
     /// Running - Process is running.
     Running,
+    /// Paused - Process is stopped by a signal. It is neither running nor done until it resumes.
+    Paused,
     /// Error - Process errored out. Allows for retry logic to kick in. Otherwise state moves to Done.
     Error,
     /// Done - Process has completed. Final state, allows for cleanup logic.
@@ -32,10 +133,13 @@ pub enum LocalCommandState {
 /// Keeps track of the final state of the process.
 #[derive(Debug, PartialEq)]
 pub enum LocalCommandDone {
-    /// Killed - Process was killed. Allows for cleanup logic.
+    /// Killed - Process was stopped or killed by a signal. Allows for cleanup logic.
     Killed,
     /// Failed - Process failed permanently. Assumes retries exhausted. Allows for cleanup logic.
     Failed,
+    /// TimedOut - Process was killed by the Timeout addon. Assumes retries exhausted. Allows for
+    /// cleanup logic.
+    TimedOut,
     /// Succeeded - Process succeeded. Allows for cleanup logic.
     Succeeded,
 }
@@ -45,11 +149,7 @@ impl LocalCommand {
     where
         S: AsRef<OsStr>,
     {
-        Self {
-            command: Command::new(program),
-            delay: None,
-            state: LocalCommandState::Ready,
-        }
+        Self::from(Command::new(program))
     }
 
     /// Adds an argument to pass to the program.
@@ -197,6 +297,221 @@ impl LocalCommand {
         self
     }
 
+    /// Configuration for the child process's standard input (stdin) handle.
+    ///
+    /// Defaults to [`StdioMode::Piped`], which lets you write to the process with
+    /// [`Process::print`](crate::Process::print) and [`Process::println`](crate::Process::println).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, StdioMode};
+    ///
+    /// let cmd = LocalCommand::new("wc").arg("-l").stdin(StdioMode::File("input.txt".into()));
+    /// ```
+    pub fn stdin(mut self, mode: StdioMode) -> Self {
+        self.stdin = mode;
+        self
+    }
+
+    /// Configuration for the child process's standard output (stdout) handle.
+    ///
+    /// Defaults to [`StdioMode::Piped`], which sends the output as
+    /// [`ProcessOutput`](crate::ProcessOutput) events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, StdioMode};
+    ///
+    /// let cmd = LocalCommand::new("ls").stdout(StdioMode::Inherit);
+    /// ```
+    pub fn stdout(mut self, mode: StdioMode) -> Self {
+        self.stdout = mode;
+        self
+    }
+
+    /// Configuration for the child process's standard error (stderr) handle.
+    ///
+    /// Defaults to [`StdioMode::Piped`], which sends the output as
+    /// [`ProcessOutput`](crate::ProcessOutput) events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, StdioMode};
+    ///
+    /// let cmd = LocalCommand::new("ls").stderr(StdioMode::Null);
+    /// ```
+    pub fn stderr(mut self, mode: StdioMode) -> Self {
+        self.stderr = mode;
+        self
+    }
+
+    /// Sets how the piped output streams of the process are read.
+    ///
+    /// Defaults to [`OutputMode::Text`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, OutputMode};
+    ///
+    /// let cmd = LocalCommand::new("cat").arg("image.png").output_mode(OutputMode::Bytes);
+    /// ```
+    pub fn output_mode(mut self, mode: OutputMode) -> Self {
+        self.output_mode = mode;
+        self
+    }
+
+    /// Splits stdout into frames with the decoder, sent as
+    /// [`ProcessOutputFrame`](crate::ProcessOutputFrame) events.
+    ///
+    /// Overrides the [`OutputMode`] of stdout, stderr is still read according to it (see
+    /// [`LocalCommand::stderr_decoder`]). Each spawned process (e.g. when retrying) gets its own
+    /// clone of the decoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, NulDelimitedDecoder};
+    ///
+    /// let cmd = LocalCommand::new("find").args([".", "-print0"]).decoder(NulDelimitedDecoder);
+    /// ```
+    pub fn decoder<D: OutputDecoder + Clone + Sync>(mut self, decoder: D) -> Self {
+        self.set_decoder(decoder);
+        self
+    }
+
+    pub(crate) fn set_decoder<D: OutputDecoder + Clone + Sync>(&mut self, decoder: D) {
+        self.decoder = Some(decoder_factory(decoder));
+    }
+
+    /// Splits stderr into frames with the decoder, sent as
+    /// [`ProcessOutputFrame`](crate::ProcessOutputFrame) events.
+    ///
+    /// Overrides the [`OutputMode`] of stderr, like [`LocalCommand::decoder`] does for stdout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LinesDecoder, LocalCommand, NulDelimitedDecoder};
+    ///
+    /// let cmd = LocalCommand::new("my-tool")
+    ///     .decoder(NulDelimitedDecoder)
+    ///     .stderr_decoder(LinesDecoder);
+    /// ```
+    pub fn stderr_decoder<D: OutputDecoder + Clone + Sync>(mut self, decoder: D) -> Self {
+        self.stderr_decoder = Some(decoder_factory(decoder));
+        self
+    }
+
+    /// Starts the process in a new process group, so that signals reach all of its descendants.
+    ///
+    /// Without this, [`Process::kill`](crate::Process::kill) only kills the program itself: the
+    /// `sleep` started by `sh -c "sleep 100"` would keep running as an orphan. With it,
+    /// [`Process::kill`](crate::Process::kill), [`Process::signal`](crate::Process::signal) and
+    /// every other way of stopping the process apply to the whole process tree.
+    ///
+    /// Note that the process will no longer receive the signals sent to the Bevy app's process
+    /// group, such as Ctrl+C in a terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::LocalCommand;
+    ///
+    /// let cmd = LocalCommand::new("sh").args(["-c", "sleep 100"]).own_process_group();
+    /// ```
+    #[cfg(unix)]
+    pub fn own_process_group(mut self) -> Self {
+        self.process_group = true;
+        self
+    }
+
+    /// Runs the process in a new pseudo-terminal of the given size, for programs that behave
+    /// differently (or refuse to run) when their output is not a terminal.
+    ///
+    /// The terminal is the stdin, stdout and stderr of the process, replacing the
+    /// [`StdioMode`]s. Everything the process writes is sent as raw terminal bytes (including
+    /// escape sequences and the echo of the input) in [`ProcessOutputBytes`] events on
+    /// [`OutputStream::Stdout`], whatever the [`OutputMode`]. Input written to the [`Process`] is
+    /// typed into the terminal.
+    ///
+    /// The process leads its own session, which implies [`LocalCommand::own_process_group`].
+    /// Use [`Process::resize_pty`] to change the size of the terminal.
+    ///
+    /// [`ProcessOutputBytes`]: crate::ProcessOutputBytes
+    /// [`OutputStream::Stdout`]: crate::OutputStream::Stdout
+    /// [`Process`]: crate::Process
+    /// [`Process::resize_pty`]: crate::Process::resize_pty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, PtySize};
+    ///
+    /// let cmd = LocalCommand::new("htop").pty(PtySize::new(40, 120));
+    /// ```
+    #[cfg(unix)]
+    pub fn pty(mut self, size: PtySize) -> Self {
+        if self.pty.is_none() {
+            crate::pty::set_controlling_terminal(&mut self.command);
+        }
+        self.pty = Some(size);
+        self.process_group = true;
+        self
+    }
+
+    /// Sets what happens to the process if it is still running when its entity is despawned, its
+    /// [`Process`](crate::Process) component is removed, or the app exits.
+    ///
+    /// Overrides the [`DropPolicy`] resource, which defaults to [`DropPolicy::Kill`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{DropPolicy, LocalCommand};
+    ///
+    /// // Keep the server running after the game closes
+    /// let cmd = LocalCommand::new("my-server").drop_policy(DropPolicy::Detach);
+    /// ```
+    pub fn drop_policy(mut self, policy: DropPolicy) -> Self {
+        self.drop_policy = Some(policy);
+        self
+    }
+
+    /// Reset the state of the last attempt so that the process can be spawned again.
+    pub(crate) fn reset_attempt(&mut self) {
+        self.delay = None;
+        self.timeout = None;
+        self.timed_out = false;
+        self.state = LocalCommandState::Ready;
+    }
+
+    /// Apply the stdio configuration to the underlying command before spawning it.
+    pub(crate) fn configure_stdio(&mut self) -> io::Result<()> {
+        let stdin = self.stdin.input()?;
+        let stdout = self.stdout.output()?;
+        let stderr = self.stderr.output()?;
+        self.command.stdin(stdin).stdout(stdout).stderr(stderr);
+        Ok(())
+    }
+
+    /// Returns the current state of the command.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy_local_commands::{LocalCommand, LocalCommandState};
+    ///
+    /// let cmd = LocalCommand::new("echo");
+    /// assert_eq!(cmd.get_state(), &LocalCommandState::Ready);
+    /// ```
+    pub fn get_state(&self) -> &LocalCommandState {
+        &self.state
+    }
+
     /// Returns the path to the program that was given to [`LocalCommand::new`].
     ///
     /// # Examples
@@ -211,6 +526,14 @@ impl LocalCommand {
         self.command.get_program()
     }
 
+    /// The file name of the program without its extension, naming the process in logs.
+    pub(crate) fn program_name(&self) -> String {
+        Path::new(self.get_program())
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
     /// Returns an iterator of the arguments that will be passed to the program.
     ///
     /// This does not include the path to the program as the first argument;
@@ -289,7 +612,24 @@ impl From<Command> for LocalCommand {
         Self {
             command,
             delay: None,
+            timeout: None,
+            timed_out: false,
             state: LocalCommandState::Ready,
+            stdin: StdioMode::default(),
+            stdout: StdioMode::default(),
+            stderr: StdioMode::default(),
+            output_mode: OutputMode::default(),
+            decoder: None,
+            stderr_decoder: None,
+            #[cfg(unix)]
+            process_group: false,
+            #[cfg(unix)]
+            pty: None,
+            drop_policy: None,
+            attempt: 0,
+            span: None,
+            delay_span: None,
+            delay_started: None,
         }
     }
 }
@@ -1,18 +1,132 @@
 use std::{
     io::{self, prelude::*, BufWriter},
-    process::{Child, ChildStdin},
+    process::Child,
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime},
 };
 
-use bevy::{prelude::*, tasks::Task};
+use bevy::{
+    ecs::{component::HookContext, world::DeferredWorld},
+    prelude::*,
+};
+use bevy_log::tracing::Span;
+use bevy_log::{info, warn};
+
+#[cfg(unix)]
+use crate::pty::PtyMaster;
+use crate::{
+    OutputStream, Pid, ProcessErrorBuffer, ProcessErrorInfo, ProcessOutputBuffer, Termination,
+};
+#[cfg(unix)]
+use crate::{PtySize, Signal};
+
+/// A background thread reading one of the process output streams into a buffer.
+#[derive(Debug)]
+pub(crate) struct OutputReader {
+    pub(crate) thread: JoinHandle<()>,
+    pub(crate) buffer: OutputBuffer,
+    /// The output read after the last newline, for text buffers.
+    pub(crate) partial_line: Option<ProcessOutputBuffer<PartialLine>>,
+}
+
+impl OutputReader {
+    /// Take the partial line at the end of the output (like the prompt of a REPL), if it is
+    /// accepted.
+    ///
+    /// The partial line is only taken once no output was read for `idle`, and once all the
+    /// complete lines before it were sent. It is then not part of the output sent as events.
+    pub(crate) fn take_partial_line(
+        &self,
+        idle: Duration,
+        accept: impl FnOnce(&str) -> bool,
+    ) -> Option<String> {
+        let OutputBuffer::Text(buffer) = &self.buffer else {
+            return None;
+        };
+        // Same locking order as the reader
+        let mut partial_line = self.partial_line.as_ref()?.0.lock().ok()?;
+        let is_idle = partial_line
+            .updated
+            .is_some_and(|updated| updated.elapsed() >= idle);
+        if partial_line.bytes.is_empty() || !is_idle || !buffer.0.lock().ok()?.is_empty() {
+            return None;
+        }
+        let line = String::from_utf8_lossy(&partial_line.bytes).into_owned();
+        if !accept(&line) {
+            return None;
+        }
+        partial_line.bytes.clear();
+        Some(line)
+    }
+}
+
+/// The output read after the last newline of a text stream, waiting for the end of the line.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PartialLine {
+    pub(crate) bytes: Vec<u8>,
+    /// When output was last read.
+    pub(crate) updated: Option<Instant>,
+}
+
+/// The buffer an output stream is read into, depending on the [`OutputMode`](crate::OutputMode)
+/// and [`OutputDecoder`](crate::OutputDecoder).
+#[derive(Debug)]
+pub(crate) enum OutputBuffer {
+    Text(ProcessOutputBuffer<String>),
+    Bytes(ProcessOutputBuffer<Vec<u8>>),
+    Frames(ProcessOutputBuffer<Vec<Vec<u8>>>),
+}
+
+/// Where input written to a [`Process`] goes: its stdin pipe, or its pseudo-terminal.
+pub(crate) trait StdinWriter: Write + std::fmt::Debug + Send + Sync {}
 
-use crate::{Pid, ProcessOutputBuffer};
+impl<W: Write + std::fmt::Debug + Send + Sync> StdinWriter for W {}
+
+/// What happens to a running process when its [`Process`] component is removed (e.g. when the
+/// entity is despawned) or when the app exits.
+///
+/// The plugin default is [`DropPolicy::Kill`], which can be changed by inserting this resource.
+/// It can be overridden for a single command with
+/// [`LocalCommand::drop_policy`](crate::LocalCommand::drop_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Resource)]
+pub enum DropPolicy {
+    /// Kill - Process is killed and waited for, so that it does not outlive the app.
+    #[default]
+    Kill,
+    /// Detach - Process keeps running on its own.
+    Detach,
+}
 
 #[derive(Debug, Component)]
+#[component(on_replace = on_replace_process)]
 pub struct Process {
     pub(crate) process: Child,
-    pub(crate) reader_task: Task<()>,
-    pub(crate) output_buffer: ProcessOutputBuffer,
-    pub(crate) stdin_writer: BufWriter<ChildStdin>,
+    pub(crate) stdout: Option<OutputReader>,
+    pub(crate) stderr: Option<OutputReader>,
+    pub(crate) stdin_writer: Option<BufWriter<Box<dyn StdinWriter>>>,
+    pub(crate) termination: Option<Termination>,
+    pub(crate) grace_period: Option<Timer>,
+    pub(crate) paused: bool,
+    #[cfg(unix)]
+    pub(crate) process_group: bool,
+    #[cfg(unix)]
+    pub(crate) pty: Option<PtyMaster>,
+    pub(crate) drop_policy: DropPolicy,
+    pub(crate) errors: ProcessErrorBuffer,
+    /// Whether the readers were finished before the output buffers were last emptied, meaning
+    /// that all the output has been sent.
+    pub(crate) output_sent: bool,
+    /// The `attempt` span, from the spawn of the process until it exits.
+    pub(crate) span: Span,
+    /// The timing of the attempt, copied to its [`ProcessTiming`] component.
+    pub(crate) timing: ProcessTiming,
+}
+
+/// Apply the drop policy of a process when its component is removed or replaced.
+fn on_replace_process(mut world: DeferredWorld, context: HookContext) {
+    if let Some(mut process) = world.get_mut::<Process>(context.entity) {
+        process.apply_drop_policy();
+    }
 }
 
 impl Process {
@@ -20,12 +134,253 @@ impl Process {
         self.process.id()
     }
 
+    /// Forcibly kill the process (`SIGKILL` on Unix).
+    ///
+    /// If the process was started with [`LocalCommand::own_process_group`], all of its descendants
+    /// are killed as well.
+    ///
+    /// [`LocalCommand::own_process_group`]: crate::LocalCommand::own_process_group
+    ///
+    /// See [`Process::stop_gracefully`] to give the process a chance to exit on its own first.
     pub fn kill(&mut self) -> io::Result<()> {
+        self.termination = Some(Termination::Forced);
+        self.grace_period = None;
+        #[cfg(unix)]
+        if self.process_group {
+            return self.signal(Signal::KILL);
+        }
         self.process.kill()
     }
 
+    /// Ask the process to terminate (`SIGTERM` on Unix), without waiting for it to exit.
+    ///
+    /// Platforms without signals have no way to ask nicely, so the process is killed instead.
+    pub fn terminate(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        return self.send_termination_signal(Signal::TERM);
+        #[cfg(not(unix))]
+        return self.kill();
+    }
+
+    /// Ask the process to terminate (`SIGTERM` on Unix), then kill it if it is still running after
+    /// the grace period.
+    ///
+    /// The way the process ended is reported in [`ProcessCompleted::termination`].
+    ///
+    /// [`ProcessCompleted::termination`]: crate::ProcessCompleted::termination
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use bevy::prelude::*;
+    /// # use bevy_local_commands::Process;
+    /// fn stop_all(mut query: Query<&mut Process>) {
+    ///     for mut process in query.iter_mut() {
+    ///         process.stop_gracefully(Duration::from_secs(5)).unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn stop_gracefully(&mut self, grace: Duration) -> io::Result<()> {
+        self.terminate()?;
+        self.start_grace_period(grace);
+        Ok(())
+    }
+
+    /// Same as [`Process::stop_gracefully`], with a custom signal instead of `SIGTERM`.
+    ///
+    /// Useful for programs that expect e.g. `SIGINT` to shut down cleanly.
+    #[cfg(unix)]
+    pub fn stop_gracefully_with_signal(
+        &mut self,
+        signal: Signal,
+        grace: Duration,
+    ) -> io::Result<()> {
+        self.send_termination_signal(signal)?;
+        self.start_grace_period(grace);
+        Ok(())
+    }
+
+    fn start_grace_period(&mut self, grace: Duration) {
+        if self.termination == Some(Termination::Graceful) {
+            self.grace_period = Some(Timer::new(grace, TimerMode::Once));
+        }
+    }
+
+    #[cfg(unix)]
+    fn send_termination_signal(&mut self, signal: Signal) -> io::Result<()> {
+        self.signal(signal)?;
+        // A stopped process only handles the signal once it is resumed
+        if self.paused {
+            self.resume()?;
+        }
+        if self.termination.is_none() {
+            self.termination = Some(Termination::Graceful);
+        }
+        Ok(())
+    }
+
+    /// Send a signal to the process, unless it has already exited.
+    ///
+    /// If the process was started with [`LocalCommand::own_process_group`], the signal is sent to
+    /// the whole process group instead.
+    ///
+    /// [`LocalCommand::own_process_group`]: crate::LocalCommand::own_process_group
+    ///
+    /// Sending [`Signal::STOP`] or [`Signal::TSTP`] moves the command to
+    /// [`LocalCommandState::Paused`](crate::LocalCommandState::Paused), and [`Signal::CONT`] moves
+    /// it back to running.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_local_commands::{Process, Signal};
+    /// fn reload_config(mut query: Query<&mut Process>) {
+    ///     for mut process in query.iter_mut() {
+    ///         process.signal(Signal::HUP).unwrap();
+    ///     }
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn signal(&mut self, signal: Signal) -> io::Result<()> {
+        let pid = self.id() as libc::pid_t;
+        let exited = self.process.try_wait()?.is_some();
+        // SAFETY: kill and killpg have no memory safety requirements
+        let result = if self.process_group {
+            // Descendants can outlive the process, and the id of their group cannot be reused
+            // until they are all gone
+            unsafe { libc::killpg(pid, signal.as_raw()) }
+        } else if exited {
+            // The pid of a process that was already waited for could have been reused
+            return Ok(());
+        } else {
+            unsafe { libc::kill(pid, signal.as_raw()) }
+        };
+        if result == -1 {
+            let error = io::Error::last_os_error();
+            if exited && error.raw_os_error() == Some(libc::ESRCH) {
+                return Ok(());
+            }
+            return Err(error);
+        }
+        if exited {
+            return Ok(());
+        }
+        match signal {
+            Signal::STOP | Signal::TSTP => self.paused = true,
+            Signal::CONT => self.paused = false,
+            _ => {},
+        }
+        Ok(())
+    }
+
+    /// Pause the process (`SIGSTOP`) until [`Process::resume`] is called.
+    ///
+    /// The command is in the [`LocalCommandState::Paused`](crate::LocalCommandState::Paused) state
+    /// in the meantime, during which the [`Timeout`](crate::Timeout) addon does not count time.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_local_commands::Process;
+    /// fn pause_all(mut query: Query<&mut Process>) {
+    ///     for mut process in query.iter_mut() {
+    ///         process.pause().unwrap();
+    ///     }
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn pause(&mut self) -> io::Result<()> {
+        self.signal(Signal::STOP)
+    }
+
+    /// Resume a process paused with [`Process::pause`] (`SIGCONT`).
+    #[cfg(unix)]
+    pub fn resume(&mut self) -> io::Result<()> {
+        self.signal(Signal::CONT)
+    }
+
+    /// Kill and wait for the process if it is still running, unless it should be detached.
+    pub(crate) fn apply_drop_policy(&mut self) {
+        if self.drop_policy == DropPolicy::Detach {
+            return;
+        }
+        if let Ok(None) = self.process.try_wait() {
+            info!("Killing process with pid {} as it is dropped", self.id());
+            if let Err(error) = self.kill() {
+                warn!("Failed to kill process with pid {}: {error}", self.id());
+            }
+            let _ = self.process.wait();
+        }
+    }
+
+    /// Whether the process is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Whether the process stdin is piped, meaning it can be written to.
+    ///
+    /// See [`LocalCommand::stdin`](crate::LocalCommand::stdin).
+    pub fn is_stdin_piped(&self) -> bool {
+        self.stdin_writer.is_some()
+    }
+
+    /// Whether the process runs in a pseudo-terminal.
+    ///
+    /// See [`LocalCommand::pty`](crate::LocalCommand::pty).
+    #[cfg(unix)]
+    pub fn is_pty(&self) -> bool {
+        self.pty.is_some()
+    }
+
+    /// Change the size of the pseudo-terminal of the process, which is sent `SIGWINCH`.
+    ///
+    /// Fails with [`io::ErrorKind::Unsupported`] if the process does not run in a
+    /// pseudo-terminal.
+    #[cfg(unix)]
+    pub fn resize_pty(&mut self, size: PtySize) -> io::Result<()> {
+        match &self.pty {
+            Some(pty) => pty.resize(size),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "process does not run in a pseudo-terminal",
+            )),
+        }
+    }
+
+    /// Whether all piped output streams have been closed by the process.
+    pub(crate) fn readers_finished(&self) -> bool {
+        self.readers()
+            .all(|(_, reader)| reader.thread.is_finished())
+    }
+
+    /// The readers of the piped output streams.
+    pub(crate) fn readers(&self) -> impl Iterator<Item = (OutputStream, &OutputReader)> {
+        [
+            (OutputStream::Stdout, self.stdout.as_ref()),
+            (OutputStream::Stderr, self.stderr.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(stream, reader)| Some((stream, reader?)))
+    }
+
+    /// Send stdin errors as [`ProcessError`](crate::ProcessError) events, on top of returning them.
+    fn report_stdin_error<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        if let Err(error) = &result {
+            self.errors
+                .push(ProcessErrorInfo::stdin_write_failed(error));
+        }
+        result
+    }
+
+    fn stdin_writer(&mut self) -> io::Result<&mut BufWriter<Box<dyn StdinWriter>>> {
+        self.stdin_writer.as_mut().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotConnected, "process stdin is not piped")
+        })
+    }
+
     /// Write a string to the process stdin.
     ///
+    /// Fails with [`io::ErrorKind::NotConnected`] if the process stdin is not piped.
+    /// Failures are also sent as [`ProcessError`](crate::ProcessError) events.
+    ///
     /// See [`Process::println`] for a version which adds a newline (`\n`) to the end of the string.
     ///
     /// Here's how you can write "Hello world!" to a process that has just been started:
@@ -110,14 +465,103 @@ impl Process {
 
 impl Write for Process {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.stdin_writer.write(buf)
+        let result = self.stdin_writer().and_then(|writer| writer.write(buf));
+        self.report_stdin_error(result)
     }
 
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        self.stdin_writer.write_all(buf)
+        let result = self.stdin_writer().and_then(|writer| writer.write_all(buf));
+        self.report_stdin_error(result)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.stdin_writer.flush()
+        let result = self.stdin_writer().and_then(|writer| writer.flush());
+        self.report_stdin_error(result)
+    }
+}
+
+/// When the last attempt of a process was spawned and how long it ran.
+///
+/// Inserted alongside [`Process`] when the process is spawned, and updated once it exits. Retries
+/// and the next steps of a [`Chain`](crate::Chain) replace it with the timing of the new attempt.
+/// The same information is sent with [`ProcessCompleted`](crate::ProcessCompleted).
+///
+/// This component is only a copy for queries: removing it does not prevent the process from
+/// completing.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_local_commands::ProcessTiming;
+///
+/// fn show_durations(query: Query<(Entity, &ProcessTiming)>) {
+///     for (entity, timing) in query.iter() {
+///         let state = if timing.completed_at().is_some() { "ran" } else { "is running" };
+///         println!(
+///             "Attempt {} of {entity} {state} for {:?}, after waiting {:?}",
+///             timing.attempt(),
+///             timing.duration(),
+///             timing.delay()
+///         );
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct ProcessTiming {
+    pub(crate) attempt: u32,
+    pub(crate) delay: Duration,
+    pub(crate) spawned_at: SystemTime,
+    pub(crate) completed_at: Option<SystemTime>,
+    /// Measures the duration, unlike [`SystemTime`] which can go backwards.
+    pub(crate) spawned: Instant,
+    pub(crate) duration: Option<Duration>,
+}
+
+impl ProcessTiming {
+    /// Start timing a process spawned now.
+    pub(crate) fn new(attempt: u32, delay: Duration) -> Self {
+        Self {
+            attempt,
+            delay,
+            spawned_at: SystemTime::now(),
+            completed_at: None,
+            spawned: Instant::now(),
+            duration: None,
+        }
+    }
+
+    /// Stop timing the process, if it was not already.
+    pub(crate) fn complete(&mut self) {
+        if self.completed_at.is_none() {
+            self.completed_at = Some(SystemTime::now());
+            self.duration = Some(self.spawned.elapsed());
+        }
+    }
+
+    /// The number of the attempt, starting at 1 and counting retries.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// How long the attempt waited for its [`Delay`](crate::Delay) (or retry backoff) before the
+    /// process was spawned.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// When the process was spawned.
+    pub fn spawned_at(&self) -> SystemTime {
+        self.spawned_at
+    }
+
+    /// When the process was seen exiting, or [`None`] while it is running.
+    pub fn completed_at(&self) -> Option<SystemTime> {
+        self.completed_at
+    }
+
+    /// How long the process ran, or has been running so far.
+    pub fn duration(&self) -> Duration {
+        self.duration.unwrap_or_else(|| self.spawned.elapsed())
     }
 }
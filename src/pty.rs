@@ -0,0 +1,140 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    process::{Child, Command, Stdio},
+    ptr,
+};
+
+/// The size of a pseudo-terminal, in characters.
+///
+/// See [`LocalCommand::pty`](crate::LocalCommand::pty).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl PtySize {
+    pub fn new(rows: u16, cols: u16) -> Self {
+        Self { rows, cols }
+    }
+
+    fn winsize(self) -> libc::winsize {
+        libc::winsize {
+            ws_row: self.rows,
+            ws_col: self.cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
+
+impl Default for PtySize {
+    /// The classic 24 rows of 80 columns.
+    fn default() -> Self {
+        Self::new(24, 80)
+    }
+}
+
+/// The controller side of a pseudo-terminal, used to talk to the process running in it.
+#[derive(Debug)]
+pub(crate) struct PtyMaster(File);
+
+impl PtyMaster {
+    pub(crate) fn try_clone(&self) -> io::Result<PtyMaster> {
+        self.0.try_clone().map(PtyMaster)
+    }
+
+    pub(crate) fn into_file(self) -> File {
+        self.0
+    }
+
+    /// Update the size of the terminal, the process is sent `SIGWINCH`.
+    pub(crate) fn resize(&self, size: PtySize) -> io::Result<()> {
+        let winsize = size.winsize();
+        // SAFETY: the file descriptor is valid and the winsize outlives the call
+        if unsafe { libc::ioctl(self.0.as_raw_fd(), libc::TIOCSWINSZ as _, &winsize) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+impl Read for PtyMaster {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.read(buf) {
+            // Linux fails with EIO instead of returning EOF once the terminal is closed
+            Err(error) if error.raw_os_error() == Some(libc::EIO) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+/// Make the process the leader of a new session, with the pseudo-terminal (its stdin) as
+/// controlling terminal, so that it gets job control and terminal signals.
+pub(crate) fn set_controlling_terminal(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: only async-signal-safe functions are called between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Spawn the command with a new pseudo-terminal of the given size as its stdin, stdout and stderr.
+pub(crate) fn spawn_in_pty(command: &mut Command, size: PtySize) -> io::Result<(Child, PtyMaster)> {
+    let (master, slave) = open(size)?;
+    command
+        .stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+    let child = command.spawn();
+
+    // Close the command's copies of the terminal, so that reading it ends with the process
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    Ok((child?, master))
+}
+
+fn open(size: PtySize) -> io::Result<(PtyMaster, OwnedFd)> {
+    let mut master = -1;
+    let mut slave = -1;
+    let mut winsize = size.winsize();
+    // SAFETY: the pointers are valid for the duration of the call, the name is not requested
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            // Mutable on some platforms
+            ptr::addr_of_mut!(winsize),
+        )
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: openpty succeeded, so both file descriptors are open and owned by us
+    let (master, slave) = unsafe { (File::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+    // Do not leak the terminal into the process beyond its stdio, nor into other processes
+    for fd in [master.as_raw_fd(), slave.as_raw_fd()] {
+        // SAFETY: the file descriptor is valid
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((PtyMaster(master), slave))
+}
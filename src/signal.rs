@@ -0,0 +1,36 @@
+/// A Unix signal that can be sent to a [`Process`](crate::Process).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signal(i32);
+
+impl Signal {
+    /// Hangup, usually asks daemons to reload their configuration.
+    pub const HUP: Signal = Signal(libc::SIGHUP);
+    /// Interrupt, as sent by Ctrl+C in a terminal.
+    pub const INT: Signal = Signal(libc::SIGINT);
+    /// Quit, as sent by Ctrl+\ in a terminal.
+    pub const QUIT: Signal = Signal(libc::SIGQUIT);
+    /// Kill, cannot be caught or ignored.
+    pub const KILL: Signal = Signal(libc::SIGKILL);
+    /// Terminate, the polite way to ask a process to exit.
+    pub const TERM: Signal = Signal(libc::SIGTERM);
+    /// User-defined signal 1.
+    pub const USR1: Signal = Signal(libc::SIGUSR1);
+    /// User-defined signal 2.
+    pub const USR2: Signal = Signal(libc::SIGUSR2);
+    /// Stop, pauses the process. Cannot be caught or ignored.
+    pub const STOP: Signal = Signal(libc::SIGSTOP);
+    /// Terminal stop, as sent by Ctrl+Z in a terminal.
+    pub const TSTP: Signal = Signal(libc::SIGTSTP);
+    /// Continue, resumes a stopped process.
+    pub const CONT: Signal = Signal(libc::SIGCONT);
+
+    /// Creates a signal from its raw number.
+    pub const fn from_raw(signal: i32) -> Self {
+        Self(signal)
+    }
+
+    /// Returns the raw number of the signal.
+    pub const fn as_raw(self) -> i32 {
+        self.0
+    }
+}
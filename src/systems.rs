@@ -1,39 +1,89 @@
-use std::io::{self, prelude::*, BufReader, BufWriter};
-use std::process::{Command, Stdio};
+use std::io::{self, prelude::*, BufWriter};
+use std::path::Path;
+use std::process::ExitStatus;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
 
-use bevy::{prelude::*, tasks::IoTaskPool};
-use bevy_log::{error, info};
+use bevy::prelude::*;
+use bevy_log::tracing::{field, span, Span};
+use bevy_log::{error, info, info_span, warn};
 
+use crate::process::{OutputBuffer, OutputReader, PartialLine, StdinWriter};
+#[cfg(unix)]
+use crate::PtySize;
 use crate::{
-    LocalCommand, LocalCommandDone, LocalCommandState, Process, ProcessCompleted, ProcessError,
-    ProcessErrorInfo, ProcessOutput, ProcessOutputBuffer,
+    Chain, DropPolicy, LocalCommand, LocalCommandDone, LocalCommandState, OutputDecoder,
+    OutputHistory, OutputMode, OutputStream, Process, ProcessCompleted, ProcessError,
+    ProcessErrorBuffer, ProcessErrorInfo, ProcessOutput, ProcessOutputBuffer, ProcessOutputBytes,
+    ProcessOutputFrame, ProcessTiming,
 };
 
 /// A command is pending process creation.
 ///
 /// This system will spawn the corresponding process if it is ready.
+///
+/// The `command` span of the command is opened the first time it is seen, as a child of the
+/// `chain` span if it is a step of a [`Chain`]. Waiting for a delay is covered by a `delay` span and
+/// each spawned process by an `attempt` span, both children of the `command` span.
 pub(crate) fn handle_new_command(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut LocalCommand), Without<Process>>,
+    mut query: Query<(Entity, &mut LocalCommand, Option<&Chain>), Without<Process>>,
     mut process_error_event: EventWriter<ProcessError>,
+    drop_policy: Res<DropPolicy>,
     time: Res<Time>,
 ) {
-    for (entity, mut local_command) in query.iter_mut() {
+    for (entity, mut local_command, chain) in query.iter_mut() {
+        if local_command.span.is_none() {
+            let parent = chain.and_then(|chain| chain.span.as_ref()?.id());
+            local_command.span = Some(command_span(entity, &local_command, parent));
+        }
         match &mut local_command.delay {
             Some(ref mut timer) if !timer.finished() => {
+                let duration = timer.duration();
                 timer.tick(time.delta());
+                if local_command.delay_span.is_none() {
+                    let parent = local_command.span.as_ref().and_then(Span::id);
+                    local_command.delay_span =
+                        Some(info_span!(parent: parent, "delay", duration = ?duration));
+                    local_command.delay_started = Some(Instant::now());
+                }
             },
             _ => {
                 local_command.delay = None;
-                match spawn_process(&mut local_command.command) {
-                    Ok(process) => {
-                        commands.entity(entity).insert(process);
+                local_command.delay_span = None;
+                let delay = local_command
+                    .delay_started
+                    .take()
+                    .map_or(Duration::ZERO, |started| started.elapsed());
+                local_command.attempt += 1;
+                let attempt_span = info_span!(
+                    parent: local_command.span.as_ref().and_then(Span::id),
+                    "attempt",
+                    attempt = local_command.attempt,
+                    pid = field::Empty,
+                    exit_code = field::Empty
+                );
+                let _entered = attempt_span.clone().entered();
+                match spawn_process(&mut local_command, *drop_policy) {
+                    Ok(mut process) => {
+                        attempt_span.record("pid", process.id());
+                        process.span = attempt_span;
+                        process.timing.delay = delay;
+                        let timing = process.timing;
+                        commands.entity(entity).insert((process, timing));
                         local_command.state = LocalCommandState::Running;
                     },
-                    Err(_) => {
+                    Err(error) => {
+                        error!("Failed to spawn command {local_command:?}: {error}");
                         process_error_event.write(ProcessError {
                             entity,
-                            info: ProcessErrorInfo::FailedToStart,
+                            info: ProcessErrorInfo::FailedToStart {
+                                kind: error.kind(),
+                                os_error: error.raw_os_error(),
+                                message: error.to_string(),
+                                program: local_command.get_program().to_owned(),
+                                current_dir: local_command.get_current_dir().map(Path::to_path_buf),
+                            },
                         });
                         local_command.state = LocalCommandState::Error;
                     },
@@ -43,67 +93,183 @@ pub(crate) fn handle_new_command(
     }
 }
 
-/// Periodically empty each processes' output buffer and send the new lines as [`ProcessOutputEvent`].
+/// Create the `command` span, covering the whole lifetime of the command.
+///
+/// The `attempts` and `exit_code` fields are recorded once the command is done, see
+/// [`close_command_span`].
+fn command_span(entity: Entity, local_command: &LocalCommand, parent: Option<span::Id>) -> Span {
+    info_span!(
+        parent: parent,
+        "command",
+        program = %local_command.get_program().to_string_lossy(),
+        args = ?local_command.get_args().collect::<Vec<_>>(),
+        entity = %entity,
+        attempts = field::Empty,
+        exit_code = field::Empty
+    )
+}
+
+/// Record the outcome of the command in its `command` span, and close it.
+fn close_command_span(local_command: &mut LocalCommand, exit_status: ExitStatus) {
+    if let Some(span) = local_command.span.take() {
+        span.record("attempts", local_command.attempt);
+        if let Some(code) = exit_status.code() {
+            span.record("exit_code", code);
+        }
+    }
+}
+
+/// Periodically empty each processes' output buffer and send the new output as [`ProcessOutput`]
+/// (or [`ProcessOutputBytes`], or [`ProcessOutputFrame`]).
+///
+/// Text output is also appended to the [`OutputHistory`] of the process, if any.
 pub(crate) fn handle_process_output(
-    query: Query<(Entity, &Process)>,
+    mut query: Query<(Entity, &mut Process, Option<&mut OutputHistory>)>,
     mut process_output_event: EventWriter<ProcessOutput>,
+    mut process_output_bytes_event: EventWriter<ProcessOutputBytes>,
+    mut process_output_frame_event: EventWriter<ProcessOutputFrame>,
 ) {
-    for (entity, process) in query.iter() {
-        if let Ok(mut buffer) = process.output_buffer.0.lock() {
+    for (entity, mut process, mut history) in query.iter_mut() {
+        // Output written after this check will be sent next frame
+        process.output_sent = process.readers_finished();
+        for (stream, reader) in process.readers() {
             // Send the buffered output in the event while clearing the output buffer
-            let mut output = String::new();
-            std::mem::swap(&mut *buffer, &mut output);
-
-            if !output.is_empty() {
-                process_output_event.write(ProcessOutput { entity, output });
+            match &reader.buffer {
+                OutputBuffer::Text(buffer) => {
+                    let output = buffer.take();
+                    if let Some(history) = history.as_mut() {
+                        let now = SystemTime::now();
+                        for line in output.lines() {
+                            history.push(stream, line, now);
+                        }
+                    }
+                    if !output.is_empty() {
+                        process_output_event.write(ProcessOutput {
+                            entity,
+                            stream,
+                            output,
+                        });
+                    }
+                },
+                OutputBuffer::Bytes(buffer) => {
+                    let bytes = buffer.take();
+                    if !bytes.is_empty() {
+                        process_output_bytes_event.write(ProcessOutputBytes {
+                            entity,
+                            stream,
+                            bytes,
+                        });
+                    }
+                },
+                OutputBuffer::Frames(buffer) => {
+                    process_output_frame_event.write_batch(buffer.take().into_iter().map(
+                        |frame| ProcessOutputFrame {
+                            entity,
+                            stream,
+                            frame,
+                        },
+                    ));
+                },
             }
         }
     }
 }
 
+/// Periodically empty each processes' error buffer and send the errors as [`ProcessError`].
+pub(crate) fn handle_process_errors(
+    query: Query<(Entity, &Process)>,
+    mut process_error_event: EventWriter<ProcessError>,
+) {
+    for (entity, process) in query.iter() {
+        if let Ok(mut errors) = process.errors.0.lock() {
+            process_error_event
+                .write_batch(errors.drain(..).map(|info| ProcessError { entity, info }));
+        }
+    }
+}
+
 /// Periodically check if any of the processes have finished.
 ///
-/// For the completed processes, a [`ProcessCompleted`] event is produced.
+/// For the completed processes, a [`ProcessCompleted`] event is produced. The [`ProcessTiming`] of
+/// a process is completed as soon as it is seen exiting.
 pub(crate) fn handle_completed_process(
-    mut query: Query<(Entity, &mut LocalCommand, &mut Process)>,
+    mut query: Query<(
+        Entity,
+        &mut LocalCommand,
+        &mut Process,
+        Option<&mut ProcessTiming>,
+    )>,
     mut process_completed_event: EventWriter<ProcessCompleted>,
 ) {
-    for (entity, mut local_command, mut process) in query.iter_mut() {
+    for (entity, mut local_command, mut process, mut timing) in query.iter_mut() {
         match local_command.state {
-            // Transition state from LocalCommandState::Error to LocalCommandDone::Failed.
-            // Retry addons should have already kicked in - unless the process failed to spawn.
+            // Transition state from LocalCommandState::Error to LocalCommandDone::Failed (or TimedOut,
+            // or Killed). Retry addons should have already kicked in - unless the process failed to
+            // spawn.
             LocalCommandState::Error => {
-                local_command.state = LocalCommandState::Done(LocalCommandDone::Failed);
-                process_completed_event.write(ProcessCompleted {
-                    entity,
-                    exit_status: process.process.wait().unwrap(),
-                });
+                let exit_status = process.process.wait().unwrap();
+                complete_timing(&mut process, timing.as_deref_mut());
+                local_command.state = if local_command.timed_out {
+                    LocalCommandState::Done(LocalCommandDone::TimedOut)
+                } else if exit_status.code().is_none() {
+                    LocalCommandState::Done(LocalCommandDone::Killed)
+                } else {
+                    LocalCommandState::Done(LocalCommandDone::Failed)
+                };
+                // Retries are over
+                close_command_span(&mut local_command, exit_status);
+                process_completed_event.write(completed_event(entity, exit_status, &process));
                 continue;
             },
             // If no cleanup addons is active, we don't want to keep checking this completed process.
             LocalCommandState::Done(_) => continue,
+            // Keep track of processes being paused and resumed.
+            LocalCommandState::Running if process.paused => {
+                local_command.state = LocalCommandState::Paused;
+            },
+            LocalCommandState::Paused if !process.paused => {
+                local_command.state = LocalCommandState::Running;
+            },
             _ => {},
         }
 
         // Deal with state management when process completes.
-        if process.reader_task.is_finished() {
-            let exit_status = process.process.wait().unwrap();
+        if !process.output_sent {
+            continue;
+        }
+        if let Ok(Some(exit_status)) = process.process.try_wait() {
+            complete_timing(&mut process, timing.as_deref_mut());
+            // The `attempt` span is closed once the process has exited
+            let span = std::mem::replace(&mut process.span, Span::none());
+            if let Some(code) = exit_status.code() {
+                span.record("exit_code", code);
+            }
+            let _entered = span.enter();
             match exit_status.code() {
-                None => {
-                    info!("Process with pid {} was killed", process.id());
+                _ if local_command.timed_out => {
+                    // The next frame will transition the state from LocalCommandState::Error to
+                    // LocalCommandDone::TimedOut if no retry addons have triggered.
+                    local_command.state = LocalCommandState::Error;
+                },
+                // A process asked to stop should not be considered as failed (and retried).
+                _ if process.termination.is_some() => {
+                    info!("Process with pid {} was stopped", process.id());
                     local_command.state = LocalCommandState::Done(LocalCommandDone::Killed);
-                    process_completed_event.write(ProcessCompleted {
-                        entity,
-                        exit_status,
-                    });
+                    close_command_span(&mut local_command, exit_status);
+                    process_completed_event.write(completed_event(entity, exit_status, &process));
+                },
+                None => {
+                    error!("Process with pid {} was killed by a signal", process.id());
+                    // The next frame will transition the state from LocalCommandState::Error to
+                    // LocalCommandDone::Killed if no retry addons have triggered. Only
+                    // RetryCondition::Signaled (or a custom condition) retries such processes.
+                    local_command.state = LocalCommandState::Error;
                 },
                 Some(0) => {
                     info!("Process with pid {} exited with code 0", process.id());
                     local_command.state = LocalCommandState::Done(LocalCommandDone::Succeeded);
-                    process_completed_event.write(ProcessCompleted {
-                        entity,
-                        exit_status,
-                    });
+                    close_command_span(&mut local_command, exit_status);
+                    process_completed_event.write(completed_event(entity, exit_status, &process));
                 },
                 Some(code) => {
                     error!(
@@ -120,48 +286,369 @@ pub(crate) fn handle_completed_process(
     }
 }
 
-pub(crate) fn spawn_process(command: &mut Command) -> io::Result<Process> {
-    // Configure the stdio to be able to read the output and send input
-    command.stdout(Stdio::piped());
-    command.stdin(Stdio::piped());
+/// Stop timing a process that exited, updating its [`ProcessTiming`] unless it was removed.
+fn complete_timing(process: &mut Process, timing: Option<&mut ProcessTiming>) {
+    process.timing.complete();
+    if let Some(timing) = timing {
+        *timing = process.timing;
+    }
+}
+
+/// The [`ProcessCompleted`] event of a process that exited.
+fn completed_event(entity: Entity, exit_status: ExitStatus, process: &Process) -> ProcessCompleted {
+    let timing = &process.timing;
+    ProcessCompleted {
+        entity,
+        exit_status,
+        termination: process.termination,
+        attempt: timing.attempt(),
+        spawned_at: timing.spawned_at(),
+        completed_at: timing.completed_at().unwrap_or(timing.spawned_at()),
+        duration: timing.duration(),
+        delay: timing.delay(),
+    }
+}
+
+/// Kill the processes that did not exit before the end of their grace period.
+///
+/// See [`Process::stop_gracefully`].
+pub(crate) fn handle_graceful_stop(mut query: Query<&mut Process>, time: Res<Time<Real>>) {
+    for mut process in query.iter_mut() {
+        let Some(timer) = &mut process.grace_period else {
+            continue;
+        };
+        if !timer.tick(time.delta()).finished() {
+            continue;
+        }
+        if let Ok(None) = process.process.try_wait() {
+            warn!(
+                "Process with pid {} did not stop within its grace period, killing it",
+                process.id()
+            );
+            if let Err(error) = process.kill() {
+                error!("Failed to kill process with pid {}: {error}", process.id());
+            }
+        }
+        process.grace_period = None;
+    }
+}
+
+/// Apply the drop policy of every process when the app exits.
+///
+/// Processes are otherwise left running, as the app may exit without dropping its world.
+pub(crate) fn handle_app_exit(
+    mut app_exit_events: EventReader<AppExit>,
+    mut query: Query<&mut Process>,
+) {
+    if app_exit_events.read().last().is_none() {
+        return;
+    }
+    for mut process in query.iter_mut() {
+        process.apply_drop_policy();
+    }
+}
+
+pub(crate) fn spawn_process(
+    local_command: &mut LocalCommand,
+    default_drop_policy: DropPolicy,
+) -> io::Result<Process> {
+    #[cfg(unix)]
+    if let Some(size) = local_command.pty {
+        return spawn_pty_process(local_command, size, default_drop_policy);
+    }
+
+    local_command.configure_stdio()?;
+    let command = &mut local_command.command;
+    #[cfg(unix)]
+    if local_command.process_group {
+        use std::os::unix::process::CommandExt;
+
+        command.process_group(0);
+    }
 
     // Start running the process
     let mut process = command.spawn()?;
-    let stdout = process.stdout.take().unwrap();
-    let stdin = process.stdin.take().unwrap();
-    let stdin_writer = BufWriter::new(stdin);
+    let errors = ProcessErrorBuffer::default();
+    let mode = local_command.output_mode;
+    let stdout = process
+        .stdout
+        .take()
+        .map(|stdout| match &local_command.decoder {
+            Some(decoder) => {
+                spawn_frame_reader(stdout, OutputStream::Stdout, decoder(), errors.clone())
+            },
+            None => spawn_reader(stdout, OutputStream::Stdout, mode, errors.clone()),
+        })
+        .transpose()?;
+    let stderr = process
+        .stderr
+        .take()
+        .map(|stderr| match &local_command.stderr_decoder {
+            Some(decoder) => {
+                spawn_frame_reader(stderr, OutputStream::Stderr, decoder(), errors.clone())
+            },
+            None => spawn_reader(stderr, OutputStream::Stderr, mode, errors.clone()),
+        })
+        .transpose()?;
+    let stdin_writer = process
+        .stdin
+        .take()
+        .map(|stdin| BufWriter::new(Box::new(stdin) as Box<dyn StdinWriter>));
     let pid = process.id();
 
     info!("Spawned command with pid {pid}: {command:?}");
 
-    let output_buffer = ProcessOutputBuffer::default();
+    Ok(Process {
+        process,
+        stdout,
+        stderr,
+        stdin_writer,
+        termination: None,
+        grace_period: None,
+        paused: false,
+        #[cfg(unix)]
+        process_group: local_command.process_group,
+        #[cfg(unix)]
+        pty: None,
+        drop_policy: local_command.drop_policy.unwrap_or(default_drop_policy),
+        errors,
+        output_sent: false,
+        span: Span::none(),
+        timing: ProcessTiming::new(local_command.attempt, Duration::ZERO),
+    })
+}
+
+/// Spawn the command in a new pseudo-terminal, reading all of its output as raw bytes.
+#[cfg(unix)]
+fn spawn_pty_process(
+    local_command: &mut LocalCommand,
+    size: PtySize,
+    default_drop_policy: DropPolicy,
+) -> io::Result<Process> {
+    let command = &mut local_command.command;
+    let (process, pty) = crate::pty::spawn_in_pty(command, size)?;
+    let errors = ProcessErrorBuffer::default();
+    let stdout = spawn_reader(
+        pty.try_clone()?,
+        OutputStream::Stdout,
+        OutputMode::Bytes,
+        errors.clone(),
+    )?;
+    let stdin_writer =
+        BufWriter::new(Box::new(pty.try_clone()?.into_file()) as Box<dyn StdinWriter>);
+    let pid = process.id();
+
+    info!(
+        "Spawned command with pid {pid} in a {}x{} pseudo-terminal: {command:?}",
+        size.cols, size.rows
+    );
 
-    let moved_buffer = output_buffer.clone();
-    let thread_pool = IoTaskPool::get();
+    Ok(Process {
+        process,
+        stdout: Some(stdout),
+        stderr: None,
+        stdin_writer: Some(stdin_writer),
+        termination: None,
+        grace_period: None,
+        paused: false,
+        process_group: true,
+        pty: Some(pty),
+        drop_policy: local_command.drop_policy.unwrap_or(default_drop_policy),
+        errors,
+        output_sent: false,
+        span: Span::none(),
+        timing: ProcessTiming::new(local_command.attempt, Duration::ZERO),
+    })
+}
 
-    // Read stdout and write it to the output buffer
-    let reader_task = thread_pool.spawn(async move {
-        let mut reader = BufReader::new(stdout);
+/// Read an output stream on its own thread and write it to a new output buffer.
+///
+/// Read failures end the reading, and are written to the error buffer.
+fn spawn_reader<R: Read + Send + 'static>(
+    stream: R,
+    output_stream: OutputStream,
+    mode: OutputMode,
+    errors: ProcessErrorBuffer,
+) -> io::Result<OutputReader> {
+    let (thread, buffer, partial_line) = match mode {
+        OutputMode::Text | OutputMode::LossyText => {
+            let buffer = ProcessOutputBuffer::default();
+            let partial_line = ProcessOutputBuffer::default();
+            let (moved_buffer, moved_partial_line) = (buffer.clone(), partial_line.clone());
+            let lossy = mode == OutputMode::LossyText;
+            let thread = spawn_reader_thread(output_stream, errors, move || {
+                read_lines(stream, lossy, moved_buffer, moved_partial_line)
+            })?;
+            (thread, OutputBuffer::Text(buffer), Some(partial_line))
+        },
+        OutputMode::Bytes => {
+            let buffer = ProcessOutputBuffer::default();
+            let moved_buffer = buffer.clone();
+            let thread = spawn_reader_thread(output_stream, errors, move || {
+                read_bytes(stream, moved_buffer)
+            })?;
+            (thread, OutputBuffer::Bytes(buffer), None)
+        },
+    };
 
-        let mut line = String::new();
+    Ok(OutputReader {
+        thread,
+        buffer,
+        partial_line,
+    })
+}
 
-        while let Ok(bytes) = reader.read_line(&mut line) {
-            if bytes == 0 {
-                break;
+/// Read an output stream on its own thread, decoding it into frames written to a new output
+/// buffer.
+///
+/// Read and decoding failures end the reading, and are written to the error buffer.
+fn spawn_frame_reader<R: Read + Send + 'static>(
+    stream: R,
+    output_stream: OutputStream,
+    decoder: Box<dyn OutputDecoder>,
+    errors: ProcessErrorBuffer,
+) -> io::Result<OutputReader> {
+    let buffer = ProcessOutputBuffer::default();
+    let moved_buffer = buffer.clone();
+    let thread = spawn_reader_thread(output_stream, errors, move || {
+        read_frames(stream, decoder, moved_buffer)
+    })?;
+
+    Ok(OutputReader {
+        thread,
+        buffer: OutputBuffer::Frames(buffer),
+        partial_line: None,
+    })
+}
+
+/// Run a blocking read loop on a dedicated thread, writing its failure to the error buffer.
+///
+/// Reads block until the process writes or closes the stream, which may never happen for
+/// long-running (or detached) processes: a shared thread pool would be starved by them, and
+/// could not read the other streams, deadlocking processes blocked on a full pipe.
+fn spawn_reader_thread(
+    output_stream: OutputStream,
+    errors: ProcessErrorBuffer,
+    read: impl FnOnce() -> io::Result<()> + Send + 'static,
+) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new()
+        .name(format!("{output_stream:?} reader"))
+        .spawn(move || {
+            if let Err(error) = read() {
+                errors.push(ProcessErrorInfo::output_read_failed(output_stream, &error));
             }
+        })
+}
 
-            if let Ok(mut buffer) = moved_buffer.0.lock() {
-                // Append the line to the buffer
-                *buffer += &line;
-                line.clear();
+/// Read a stream line by line until it is closed, appending each line to the buffer.
+///
+/// The output read after the last newline is kept in the partial line, so that it can be taken
+/// before its line ends (e.g. the prompt of a REPL, see [`Interactive`](crate::Interactive)).
+fn read_lines(
+    mut stream: impl Read,
+    lossy: bool,
+    buffer: ProcessOutputBuffer,
+    partial_line: ProcessOutputBuffer<PartialLine>,
+) -> io::Result<()> {
+    let mut chunk = [0; 8192];
+
+    loop {
+        let bytes = match stream.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        let Ok(mut partial_line) = partial_line.0.lock() else {
+            return Ok(());
+        };
+        partial_line.bytes.extend_from_slice(&chunk[..bytes]);
+        partial_line.updated = Some(Instant::now());
+        if let Some(end) = partial_line.bytes.iter().rposition(|&byte| byte == b'\n') {
+            let lines: Vec<u8> = partial_line.bytes.drain(..=end).collect();
+            append_lines(&lines, lossy, &buffer)?;
+        }
+    }
+
+    // The last line may not end with a newline
+    let last_line = partial_line.take().bytes;
+    append_lines(&last_line, lossy, &buffer)
+}
+
+/// Decode complete lines and append them to the buffer, one at a time.
+fn append_lines(lines: &[u8], lossy: bool, buffer: &ProcessOutputBuffer) -> io::Result<()> {
+    for line in lines.split_inclusive(|&byte| byte == b'\n') {
+        let line = if lossy {
+            String::from_utf8_lossy(line)
+        } else {
+            std::str::from_utf8(line)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+                .into()
+        };
+
+        if let Ok(mut buffer) = buffer.0.lock() {
+            // Append the line to the buffer
+            *buffer += &line;
+        }
+    }
+    Ok(())
+}
+
+/// Read a stream chunk by chunk until it is closed, appending each chunk to the buffer.
+fn read_bytes(mut stream: impl Read, buffer: ProcessOutputBuffer<Vec<u8>>) -> io::Result<()> {
+    let mut chunk = [0; 8192];
+
+    loop {
+        let bytes = match stream.read(&mut chunk) {
+            Ok(0) => return Ok(()),
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        if let Ok(mut buffer) = buffer.0.lock() {
+            // Append the chunk to the buffer
+            buffer.extend_from_slice(&chunk[..bytes]);
+        }
+    }
+}
+
+/// Read a stream chunk by chunk until it is closed, appending each decoded frame to the buffer.
+fn read_frames(
+    mut stream: impl Read,
+    mut decoder: Box<dyn OutputDecoder>,
+    buffer: ProcessOutputBuffer<Vec<Vec<u8>>>,
+) -> io::Result<()> {
+    let mut chunk = [0; 8192];
+    let mut pending = Vec::new();
+
+    loop {
+        let bytes = match stream.read(&mut chunk) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        pending.extend_from_slice(&chunk[..bytes]);
+
+        let mut frames = Vec::new();
+        if bytes == 0 {
+            while let Some(frame) = decoder.decode_eof(&mut pending)? {
+                frames.push(frame);
+            }
+        } else {
+            while let Some(frame) = decoder.decode(&mut pending)? {
+                frames.push(frame);
             }
         }
-    });
 
-    Ok(Process {
-        process,
-        output_buffer,
-        reader_task,
-        stdin_writer,
-    })
+        if let Ok(mut buffer) = buffer.0.lock() {
+            // Append the frames to the buffer
+            buffer.append(&mut frames);
+        }
+
+        if bytes == 0 {
+            return Ok(());
+        }
+    }
 }